@@ -0,0 +1,79 @@
+#![no_main]
+
+use calc::{Calculator, Limits};
+use libfuzzer_sys::fuzz_target;
+use std::time::Duration;
+
+const NUMBERS: &[&str] = &["0", "1", "2", "7", "0.5", "100", "1e300", "1e-300", "1e5000", "12345678901234567890"];
+const WORDS: &[&str] = &["x", "k", "P", "Y", "C", "L", "m", "s", "kg", "km", "h", "degC", "inf"];
+const OPERATORS: &[&str] = &["+", "-", "*", "/", "%", "^", " to "];
+const MATH: &[&str] = &[
+    "sin", "cos", "tan", "sqrt", "ln", "log", "log2", "exp", "gamma", "zeta", "abs", "floor",
+    "trunc", "frac", "recip", "eint", "li2", "digamma", "acosh", "atanh", "asec", "acoth",
+    "lngamma", "ellipk", "ellipe", "sinc", "versin", "gd",
+];
+const SPECIAL: &[&str] = &[
+    "fac", "ncr", "npr", "sum", "prod", "integrate", "diff", "solve", "mean", "median", "mode",
+    "var", "stdev", "min", "max", "percentile", "geomean", "det", "inv", "transpose", "dot",
+    "cross", "norm", "rank", "roots", "polyval", "polyder", "polyfit", "gcd", "lcm", "isprime",
+    "nextprime", "prevprime", "factor", "totient", "modpow", "modinv", "jacobi", "rand",
+    "randint", "randn", "choose", "normcdf", "norminv", "tcdf", "tinv", "chisqinv", "finv",
+    "binomcdf", "poisscdf", "besselj", "bessely", "agm", "beta", "gammainc", "lambertw",
+    "hypot", "multinomial", "subfactorial", "catalan", "stirling", "bell",
+];
+const MAX_DEPTH: u32 = 6;
+
+struct Source<'a> {
+    data: &'a [u8],
+    cursor: usize,
+}
+
+impl Source<'_> {
+    fn byte(&mut self) -> u8 {
+        let byte = self.data.get(self.cursor).copied().unwrap_or(0);
+        self.cursor += 1;
+        byte
+    }
+
+    fn pick(&mut self, items: &[&'static str]) -> &'static str {
+        items[self.byte() as usize % items.len()]
+    }
+
+    fn args(&mut self, depth: u32) -> String {
+        let count = self.byte() % 5;
+        (0..count).map(|_| self.expr(depth + 1)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn expr(&mut self, depth: u32) -> String {
+        let mut expr = self.term(depth);
+        for _ in 0..self.byte() % 3 {
+            expr.push_str(self.pick(OPERATORS));
+            expr.push_str(&self.term(depth));
+        }
+        expr
+    }
+
+    fn term(&mut self, depth: u32) -> String {
+        let choice = if depth >= MAX_DEPTH { self.byte() % 2 } else { self.byte() % 7 };
+        match choice {
+            0 => self.pick(NUMBERS).to_string(),
+            1 => self.pick(WORDS).to_string(),
+            2 => format!("-{}", self.term(depth + 1)),
+            3 => format!("({})", self.expr(depth + 1)),
+            4 => format!("{}({})", self.pick(MATH), self.expr(depth + 1)),
+            5 => format!("{}({})", self.pick(SPECIAL), self.args(depth)),
+            _ => format!("[{}]", self.args(depth)),
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let expr = Source { data, cursor: 0 }.expr(0);
+    let mut calc = Calculator::new(128);
+    calc.set_limits(Limits { timeout: Some(Duration::from_millis(200)), ..Limits::default() });
+    let _ = calc.run(&expr).and_then(|value| value.to_grid(Some(6)));
+    calc.reset();
+    let _ = calc.run_round(&expr, Some(6));
+    calc.reset();
+    let _ = calc.run_round(&expr, None);
+});
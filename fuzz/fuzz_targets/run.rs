@@ -0,0 +1,19 @@
+#![no_main]
+
+use calc::{Calculator, Limits};
+use libfuzzer_sys::fuzz_target;
+use std::time::Duration;
+
+fuzz_target!(|data: &[u8]| {
+    let mut calc = Calculator::new(128);
+    calc.set_limits(Limits { timeout: Some(Duration::from_millis(200)), ..Limits::default() });
+    let _ = calc.run(data).and_then(|value| value.to_grid(Some(6)));
+    calc.reset();
+    let _ = calc.run_round(data, Some(6));
+    calc.reset();
+    let _ = calc.run_round(data, None);
+    calc.reset();
+    for statement in calc.run_script(data) {
+        let _ = statement.result.and_then(|value| value.to_round(Some(6)));
+    }
+});
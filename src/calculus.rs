@@ -0,0 +1,358 @@
+use rug::Float;
+use rug::ops::Pow;
+use rug::float::Constant;
+use std::cmp::max;
+use crate::{identifier, matrix, Calculator, CalcError, FloatExt, Value};
+
+const LEVELS: u32 = 8;
+const MAX_ORDER: u32 = 32;
+const MAX_LEVEL: u32 = 12;
+const MAX_ITER: u32 = 200;
+
+fn order(calc: &Calculator, expr: Option<&&[u8]>) -> Result<u32, CalcError> {
+    let expr = match expr {
+        Some(expr) => expr,
+        None => return Ok(1),
+    };
+    let n = calc.scope(calc.context.prec).evaluate(expr)?;
+    if !n.is_integer() || !(0..=MAX_ORDER).contains(&n) {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(n.to_u32_saturating().unwrap_or(0))
+}
+
+fn central(
+    scope: &mut Calculator, expr: &[u8], var: &[u8], x0: &Float, h: &Float, n: u32
+) -> Result<Float, CalcError> {
+    let prec = scope.context.prec;
+    let mut sum = Float::with_val(prec, 0);
+    let mut coef = Float::with_val(prec, 1);
+    for k in 0..=n {
+        let shift = Float::with_val(prec, n as i32 - 2 * k as i32) / 2u32 * h;
+        scope.bind(var, Value::Real(Float::with_val(prec, x0 + &shift)));
+        let fx = scope.evaluate(expr)?;
+        if k.is_multiple_of(2) {
+            sum += &coef * fx;
+        } else {
+            sum -= &coef * fx;
+        }
+        coef = coef * (n - k) / (k + 1);
+    }
+    Ok(sum / h.clone().pow(n))
+}
+
+pub fn diff(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    if args.len() != 3 && args.len() != 4 {
+        return Err(CalcError::ParameterError);
+    }
+    let (expr, var) = (args[0], identifier(args[1])?);
+    let n = order(calc, args.get(3))?;
+    let prec = calc.context.prec;
+    let step = max(prec / (2 * LEVELS + 2), 8);
+    let work = prec + n * (step + LEVELS) + 64;
+    let mut scope = calc.scope(work);
+    let x0 = scope.evaluate(args[2])?;
+    if n == 0 {
+        scope.bind(var, Value::Real(x0));
+        let value = scope.evaluate(expr)?;
+        return Ok(Value::Real(Float::with_val(prec, value).accuracy(&calc.context)?));
+    }
+    let mut h = Float::with_val(work, Float::i_exp(1, -(step as i32)));
+    let mut prev: Vec<Float> = Vec::with_capacity(LEVELS as usize + 1);
+    for _ in 0..=LEVELS {
+        let mut row = vec![central(&mut scope, expr, var, &x0, &h, n)?];
+        let mut factor = Float::with_val(work, 1);
+        for (j, last) in prev.iter().enumerate() {
+            factor *= 4;
+            let delta = Float::with_val(work, &row[j] - last);
+            let next = Float::with_val(work, &row[j] + delta / Float::with_val(work, &factor - 1));
+            row.push(next);
+        }
+        prev = row;
+        h /= 2;
+    }
+    let value = prev.pop().unwrap_or_else(|| Float::new(work));
+    Ok(Value::Real(Float::with_val(prec, value).accuracy(&calc.context)?))
+}
+
+fn sample(
+    scope: &mut Calculator, expr: &[u8], var: &[u8], x: Float, end: &Float
+) -> Result<Option<Float>, CalcError> {
+    if x == *end {
+        return Ok(None);
+    }
+    scope.bind(var, Value::Real(x));
+    match scope.evaluate(expr) {
+        Err(CalcError::BeyondAccuracy) => Ok(None),
+        result => result.map(Some),
+    }
+}
+
+fn quadrature(calc: &Calculator, args: &[&[u8]]) -> Result<(Float, Float), CalcError> {
+    if args.len() != 4 {
+        return Err(CalcError::ParameterError);
+    }
+    let (expr, var) = (args[0], identifier(args[1])?);
+    let prec = calc.context.prec;
+    let work = prec * 2 + 64;
+    let mut scope = calc.scope(work);
+    let a = scope.evaluate(args[2])?;
+    let b = scope.evaluate(args[3])?;
+    if a == b {
+        return Ok((Float::with_val(prec, 0), Float::with_val(prec, 0)));
+    }
+    let c = Float::with_val(work, &a + &b) / 2u32;
+    let d = Float::with_val(work, &b - &a) / 2u32;
+    let half_pi = Float::with_val(work, Constant::Pi) / 2u32;
+    let tiny = Float::with_val(work, Float::i_exp(1, -(work as i32) - 32));
+    let floor = Float::with_val(work, tiny.square_ref());
+    let tol = Float::with_val(work, Float::i_exp(1, -(prec as i32)));
+    scope.bind(var, Value::Real(c.clone()));
+    let mut sum = Float::with_val(work, scope.evaluate(expr)? * &half_pi);
+    let mut estimate: Option<Float> = None;
+    let mut prev = Float::with_val(work, &sum * 1u32);
+    let mut h = Float::with_val(work, 1);
+    for level in 0..=MAX_LEVEL {
+        let step = if level == 0 { 1 } else { 2 };
+        let growth = Float::with_val(work, &h * step).exp();
+        let mut et = Float::with_val(work, h.exp_ref());
+        let (mut left, mut right) = (true, true);
+        while left || right {
+            calc.context.check()?;
+            let inv = Float::with_val(work, et.recip_ref());
+            let sinh = Float::with_val(work, &et - &inv) / 2u32;
+            let cosh = Float::with_val(work, &et + &inv) / 2u32;
+            let q = Float::with_val(work, sinh * &half_pi * -2i32).exp();
+            let q1 = Float::with_val(work, &q + 1u32);
+            let delta = Float::with_val(work, &q * 2u32) / &q1;
+            let weight = cosh * &half_pi * 4u32 * q / q1.square();
+            if weight < floor {
+                break;
+            }
+            let offset = Float::with_val(work, &d * &delta);
+            if left {
+                let x = Float::with_val(work, &a + &offset);
+                match sample(&mut scope, expr, var, x, &a)? {
+                    Some(fx) => {
+                        let term = fx * &weight;
+                        left = term.clone().abs() >= tiny;
+                        sum += term;
+                    },
+                    None => left = false,
+                }
+            }
+            if right {
+                let x = Float::with_val(work, &b - &offset);
+                match sample(&mut scope, expr, var, x, &b)? {
+                    Some(fx) => {
+                        let term = fx * &weight;
+                        right = term.clone().abs() >= tiny;
+                        sum += term;
+                    },
+                    None => right = false,
+                }
+            }
+            et *= &growth;
+        }
+        let total = Float::with_val(work, &sum * &h) * &d;
+        let change = Float::with_val(work, &total - &prev).abs();
+        let scale = Float::with_val(work, total.abs_ref()).max(&Float::with_val(work, 1));
+        let error = match &estimate {
+            Some(last) if *last > 0 => Float::with_val(work, change.square_ref()) / last,
+            _ => change.clone(),
+        };
+        if level > 2 && (change == 0 || error <= Float::with_val(work, &tol * &scale)) {
+            let total = Float::with_val(prec, total).accuracy(&calc.context)?;
+            return Ok((total, Float::with_val(prec, error)));
+        }
+        estimate = Some(change);
+        prev = total;
+        h /= 2;
+    }
+    Err(CalcError::NoConvergence)
+}
+
+pub fn integrate(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    quadrature(calc, args).map(|(total, _)| Value::Real(total))
+}
+
+pub fn integrate_err(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    quadrature(calc, args).map(|(total, error)| Value::List(vec![total, error]))
+}
+
+fn at(scope: &mut Calculator, expr: &[u8], var: &[u8], x: &Float) -> Result<Float, CalcError> {
+    scope.bind(var, Value::Real(x.clone()));
+    scope.evaluate(expr)
+}
+
+fn newton(
+    scope: &mut Calculator, expr: &[u8], var: &[u8], guess: Float, tol: &Float
+) -> Result<Float, CalcError> {
+    let work = scope.context.prec;
+    let one = Float::with_val(work, 1);
+    let mut x = guess;
+    let mut fx = at(scope, expr, var, &x)?;
+    let limit = Float::with_val(work, fx.abs_ref()) * tol;
+    let mut last: Option<Float> = None;
+    let mut trend: Option<Float> = None;
+    for _ in 0..MAX_ITER {
+        if fx == 0 || fx.clone().abs() <= limit {
+            return Ok(x);
+        }
+        let scale = Float::with_val(work, x.abs_ref()).max(&one);
+        let h = Float::with_val(work, Float::i_exp(1, -(work as i32) / 3)) * &scale;
+        let f1 = at(scope, expr, var, &Float::with_val(work, &x + &h))
+            .map_err(|_| CalcError::NoConvergence)?;
+        let f2 = at(scope, expr, var, &Float::with_val(work, &x - &h))
+            .map_err(|_| CalcError::NoConvergence)?;
+        let slope = (f1 - f2) / (h * 2u32);
+        if slope == 0 || !slope.is_finite() {
+            return Err(CalcError::NoConvergence);
+        }
+        let mut dx = Float::with_val(work, &fx / &slope);
+        let mut next = None;
+        if let Some(last) = &last {
+            let ratio = Float::with_val(work, &dx / last);
+            let steady = trend.as_ref().is_some_and(|trend| {
+                Float::with_val(work, &ratio - trend).abs() < 0.05
+            });
+            trend = Some(ratio.clone());
+            if steady && ratio > 0.4 && ratio < 1 {
+                let multiple = (one.clone() / (one.clone() - ratio)).round();
+                let jump = Float::with_val(work, &dx * &multiple);
+                let y = Float::with_val(work, &x - &jump);
+                if let Ok(fy) = at(scope, expr, var, &y) {
+                    if fy.clone().abs() < fx.clone().abs() {
+                        next = Some((y, fy));
+                        dx = jump;
+                    }
+                }
+            }
+        }
+        match next {
+            Some((y, fy)) => {
+                x = y;
+                fx = fy;
+            },
+            None => {
+                x -= &dx;
+                fx = at(scope, expr, var, &x)
+                    .map_err(|_| CalcError::NoConvergence)?;
+            }
+        }
+        if dx.clone().abs() <= Float::with_val(work, tol * &scale) {
+            return Ok(x);
+        }
+        last = Some(dx);
+    }
+    Err(CalcError::NoConvergence)
+}
+
+fn brent(
+    scope: &mut Calculator, expr: &[u8], var: &[u8], a: Float, b: Float, tol: &Float
+) -> Result<Float, CalcError> {
+    let work = scope.context.prec;
+    let one = Float::with_val(work, 1);
+    let (mut a, mut b) = (a, b);
+    let mut fa = at(scope, expr, var, &a)?;
+    let mut fb = at(scope, expr, var, &b)?;
+    if fa == 0 {
+        return Ok(a);
+    } else if fb == 0 {
+        return Ok(b);
+    } else if fa.is_sign_negative() == fb.is_sign_negative() {
+        return Err(CalcError::ParameterError);
+    }
+    let (mut c, mut fc) = (b.clone(), fb.clone());
+    let mut d = Float::with_val(work, &b - &a);
+    let mut e = d.clone();
+    for _ in 0..MAX_ITER * 16 {
+        if fb.is_sign_negative() == fc.is_sign_negative() {
+            c = a.clone();
+            fc = fa.clone();
+            d = Float::with_val(work, &b - &a);
+            e = d.clone();
+        }
+        if fc.clone().abs() < fb.clone().abs() {
+            a = b.clone();
+            b = c.clone();
+            c = a.clone();
+            fa = fb.clone();
+            fb = fc.clone();
+            fc = fa.clone();
+        }
+        let tol1 = Float::with_val(work, b.abs_ref()).max(&one) * tol;
+        let xm = Float::with_val(work, &c - &b) / 2u32;
+        if xm.clone().abs() <= tol1 || fb == 0 {
+            return Ok(b);
+        }
+        if e.clone().abs() >= tol1 && fa.clone().abs() > fb.clone().abs() {
+            let s = Float::with_val(work, &fb / &fa);
+            let (mut p, mut q);
+            if a == c {
+                p = Float::with_val(work, &xm * &s) * 2u32;
+                q = Float::with_val(work, 1 - &s);
+            } else {
+                let qa = Float::with_val(work, &fa / &fc);
+                let r = Float::with_val(work, &fb / &fc);
+                let span = Float::with_val(work, &b - &a) * Float::with_val(work, &r - 1u32);
+                let inner = Float::with_val(work, &xm * 2u32) * &qa * Float::with_val(work, &qa - &r);
+                p = Float::with_val(work, &s * (inner - span));
+                q = (qa - 1u32) * (r - 1u32) * Float::with_val(work, &s - 1u32);
+            }
+            if p > 0 {
+                q = -q;
+            }
+            p = p.abs();
+            let min1 = Float::with_val(work, &xm * &q) * 3u32 - Float::with_val(work, &tol1 * &q).abs();
+            let min2 = Float::with_val(work, &e * &q).abs();
+            if Float::with_val(work, &p * 2u32) < min1.min(&min2) {
+                e = d.clone();
+                d = p / q;
+            } else {
+                d = xm.clone();
+                e = d.clone();
+            }
+        } else {
+            d = xm.clone();
+            e = d.clone();
+        }
+        a = b.clone();
+        fa = fb.clone();
+        if d.clone().abs() > tol1 {
+            b += &d;
+        } else if xm.is_sign_negative() {
+            b -= &tol1;
+        } else {
+            b += &tol1;
+        }
+        fb = at(scope, expr, var, &b)?;
+    }
+    Err(CalcError::NoConvergence)
+}
+
+pub fn solve(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    if args.len() == 2 {
+        return matrix::solve(calc, args);
+    } else if args.len() != 3 && args.len() != 4 {
+        return Err(CalcError::ParameterError);
+    }
+    let (expr, var) = (args[0], identifier(args[1])?);
+    let prec = calc.context.prec;
+    let work = prec + 64;
+    let mut scope = calc.scope(work);
+    let tol = Float::with_val(work, Float::i_exp(1, -(prec as i32)));
+    let root = match args {
+        [_, _, guess] => {
+            let guess = scope.evaluate(guess)?;
+            newton(&mut scope, expr, var, guess, &tol)?
+        },
+        [_, _, a, b] => {
+            let a = scope.evaluate(a)?;
+            let b = scope.evaluate(b)?;
+            brent(&mut scope, expr, var, a, b, &tol)?
+        },
+        _ => return Err(CalcError::ParameterError),
+    };
+    Ok(Value::Real(Float::with_val(prec, root).accuracy(&calc.context)?))
+}
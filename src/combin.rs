@@ -0,0 +1,123 @@
+use rug::{Complete, Integer};
+use crate::{Calculator, CalcError, Value};
+
+const MAX_TABLE: u32 = 2_000;
+
+pub fn integers<const N: usize>(calc: &Calculator, args: &[&[u8]]) -> Result<[Integer; N], CalcError> {
+    let mut scope = calc.scope(calc.context.prec);
+    let values = args.iter().map(|arg| scope.value(arg)?.integer())
+        .collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|_| CalcError::ParameterError)
+}
+
+fn count(n: &Integer, limit: u32) -> Result<u32, CalcError> {
+    if *n < 0 {
+        return Err(CalcError::ParameterError);
+    }
+    n.to_u32().filter(|&n| n <= limit).ok_or(CalcError::LimitExceeded)
+}
+
+pub fn fac(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = integers(calc, args)?;
+    let n = count(&n, calc.context.limits.factorial)?;
+    Ok(Value::Integer(Integer::factorial(n).complete()))
+}
+
+pub fn ncr(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n, k] = integers(calc, args)?;
+    count(&n, u32::MAX)?;
+    let k = count(&k, calc.context.limits.factorial)?;
+    Ok(Value::Integer(n.binomial(k)))
+}
+
+pub fn npr(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n, k] = integers(calc, args)?;
+    count(&n, u32::MAX)?;
+    let k = count(&k, calc.context.limits.factorial)?;
+    Ok(Value::Integer(n.binomial(k) * Integer::factorial(k).complete()))
+}
+
+pub fn multinomial(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let mut scope = calc.scope(calc.context.prec);
+    let mut total = 0u32;
+    let mut result = Integer::from(1);
+    let limit = calc.context.limits.factorial;
+    for arg in args {
+        let k = count(&scope.value(arg)?.integer()?, limit)?;
+        total = total.checked_add(k).filter(|&n| n <= limit)
+            .ok_or(CalcError::LimitExceeded)?;
+        result *= Integer::binomial_u(total, k).complete();
+    }
+    Ok(Value::Integer(result))
+}
+
+pub fn subfactorial(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = integers(calc, args)?;
+    let n = count(&n, calc.context.limits.factorial)?;
+    let mut result = Integer::from(1);
+    for k in 1..=n {
+        calc.context.check()?;
+        result *= k;
+        if k % 2 == 1 {
+            result -= 1;
+        } else { result += 1; }
+    }
+    Ok(Value::Integer(result))
+}
+
+pub fn catalan(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = integers(calc, args)?;
+    let n = count(&n, calc.context.limits.factorial)?;
+    let double = n.checked_mul(2).ok_or(CalcError::LimitExceeded)?;
+    Ok(Value::Integer(Integer::binomial_u(double, n).complete() / (Integer::from(n) + 1u32)))
+}
+
+fn triangle(calc: &Calculator, n: u32, k: u32, first: bool) -> Result<Integer, CalcError> {
+    let mut row = vec![Integer::from(1)];
+    for i in 0..n {
+        calc.context.check()?;
+        let mut next = vec![Integer::new(); row.len() + 1];
+        for (j, value) in row.iter().enumerate() {
+            let weight = if first { i } else { j as u32 };
+            next[j] += value * Integer::from(weight);
+            next[j + 1] += value;
+        }
+        row = next;
+    }
+    Ok(row.swap_remove(k as usize))
+}
+
+pub fn stirling(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n, k] = integers(calc, args)?;
+    let (n, k) = (count(&n, MAX_TABLE)?, count(&k, u32::MAX)?);
+    if k > n {
+        return Ok(Value::Integer(Integer::new()));
+    }
+    triangle(calc, n, k, false).map(Value::Integer)
+}
+
+pub fn stirlingf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n, k] = integers(calc, args)?;
+    let (n, k) = (count(&n, MAX_TABLE)?, count(&k, u32::MAX)?);
+    if k > n {
+        return Ok(Value::Integer(Integer::new()));
+    }
+    triangle(calc, n, k, true).map(Value::Integer)
+}
+
+pub fn bell(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = integers(calc, args)?;
+    let n = count(&n, MAX_TABLE)?;
+    let mut row = vec![Integer::from(1)];
+    for _ in 0..n {
+        calc.context.check()?;
+        let mut next = Vec::with_capacity(row.len() + 1);
+        next.push(row[row.len() - 1].clone());
+        for value in &row {
+            let sum = Integer::from(&next[next.len() - 1] + value);
+            next.push(sum);
+        }
+        row = next;
+    }
+    Ok(Value::Integer(row.swap_remove(0)))
+}
@@ -0,0 +1,357 @@
+use rug::Float;
+use rug::float::{Constant, Special};
+use std::cmp::max;
+use std::ops::RangeInclusive;
+use crate::{special, Calculator, CalcError, FloatExt, Value};
+
+type Density = fn(&Float, &[Float], u32) -> Result<Float, CalcError>;
+
+fn params(calc: &Calculator, args: &[&[u8]], arity: RangeInclusive<usize>) -> Result<(Vec<Float>, u32), CalcError> {
+    if !arity.contains(&args.len()) {
+        return Err(CalcError::ParameterError);
+    }
+    let work = calc.context.prec + 64;
+    let mut scope = calc.scope(calc.context.prec);
+    let values = args.iter().map(|arg| Ok(Float::with_val(work, scope.evaluate(arg)?)))
+        .collect::<Result<Vec<_>, CalcError>>()?;
+    Ok((values, work))
+}
+
+fn result(calc: &Calculator, value: Float) -> Result<Value, CalcError> {
+    Ok(Value::Real(Float::with_val(calc.context.prec, value).accuracy(&calc.context)?))
+}
+
+fn positive(values: &[Float]) -> Result<(), CalcError> {
+    if values.iter().all(|v| *v > 0 || v.is_nan()) {
+        Ok(())
+    } else { Err(CalcError::ParameterError) }
+}
+
+fn probability(p: &Float) -> Result<(), CalcError> {
+    if *p >= 0 && *p <= 1 {
+        Ok(())
+    } else { Err(CalcError::ParameterError) }
+}
+
+fn count(k: &Float) -> Result<(), CalcError> {
+    if k.is_integer() && *k >= 0 {
+        Ok(())
+    } else { Err(CalcError::ParameterError) }
+}
+
+fn invert(
+    calc: &Calculator, cdf: Density, pdf: Density, p: &Float, params: &[Float], lower: Option<Float>, work: u32
+) -> Result<Float, CalcError> {
+    if p.is_nan() || params.iter().any(|v| !v.is_finite()) {
+        return Ok(Float::with_val(work, Special::Nan));
+    }
+    let tol = Float::with_val(work, Float::i_exp(1, 64 - work as i32));
+    let mut hi = Float::with_val(work, 1);
+    while cdf(&hi, params, work)? < *p {
+        calc.context.check()?;
+        hi *= 2u32;
+        if hi.get_exp().unwrap_or(0) > 64 {
+            return Err(CalcError::NoConvergence);
+        }
+    }
+    let mut lo = match lower {
+        Some(lower) => lower,
+        None => {
+            let mut lo = Float::with_val(work, -1);
+            while cdf(&lo, params, work)? > *p {
+                calc.context.check()?;
+                lo *= 2u32;
+                if lo.get_exp().unwrap_or(0) > 64 {
+                    return Err(CalcError::NoConvergence);
+                }
+            }
+            lo
+        },
+    };
+    let mut x = Float::with_val(work, &lo + &hi) / 2u32;
+    for _ in 0..max(1000, 4 * work) {
+        calc.context.check()?;
+        let diff = Float::with_val(work, cdf(&x, params, work)? - p);
+        if diff > 0 {
+            hi = x.clone();
+        } else { lo = x.clone(); }
+        let slope = pdf(&x, params, work)?;
+        let newton = Float::with_val(work, &x - Float::with_val(work, &diff / &slope));
+        let next = if slope > 0 && newton >= lo && newton <= hi {
+            newton
+        } else { Float::with_val(work, &lo + &hi) / 2u32 };
+        let scale = Float::with_val(work, next.abs_ref()).max(&Float::with_val(work, 1));
+        let step = Float::with_val(work, &next - &x).abs();
+        x = next;
+        if step <= Float::with_val(work, &tol * &scale) || hi <= lo {
+            return Ok(x);
+        }
+    }
+    Err(CalcError::NoConvergence)
+}
+
+fn norm_pdf(z: &Float, _: &[Float], work: u32) -> Result<Float, CalcError> {
+    let root = Float::with_val(work, Constant::Pi) * 2u32;
+    Ok(Float::with_val(work, -Float::with_val(work, z.square_ref()) / 2u32).exp() / root.sqrt())
+}
+
+fn norm_cdf(z: &Float, _: &[Float], work: u32) -> Result<Float, CalcError> {
+    let root = Float::with_val(work, 2u32).sqrt();
+    Ok((-Float::with_val(work, z / root)).erfc() / 2u32)
+}
+
+fn standard(values: &[Float], work: u32) -> Result<(Float, Float, Float), CalcError> {
+    let (x, mu, sigma) = match values {
+        [x] => (x.clone(), Float::with_val(work, 0), Float::with_val(work, 1)),
+        [x, mu, sigma] => (x.clone(), mu.clone(), sigma.clone()),
+        _ => return Err(CalcError::ParameterError),
+    };
+    positive(std::slice::from_ref(&sigma))?;
+    Ok((x, mu, sigma))
+}
+
+pub fn normpdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 1..=3)?;
+    let (x, mu, sigma) = standard(&values, work)?;
+    let z = Float::with_val(work, &x - &mu) / &sigma;
+    result(calc, norm_pdf(&z, &[], work)? / sigma)
+}
+
+pub fn normcdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 1..=3)?;
+    let (x, mu, sigma) = standard(&values, work)?;
+    let z = Float::with_val(work, &x - &mu) / &sigma;
+    result(calc, norm_cdf(&z, &[], work)?)
+}
+
+pub fn norminv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 1..=3)?;
+    let (p, mu, sigma) = standard(&values, work)?;
+    if p <= 0 || p >= 1 {
+        return Err(CalcError::ParameterError);
+    }
+    let z = invert(calc, norm_cdf, norm_pdf, &p, &[], None, work)?;
+    result(calc, z * sigma + mu)
+}
+
+fn t_pdf(x: &Float, params: &[Float], work: u32) -> Result<Float, CalcError> {
+    let nu = &params[0];
+    let half = Float::with_val(work, nu + 1u32) / 2u32;
+    let log = Float::with_val(work, half.ln_gamma_ref())
+        - Float::with_val(work, nu / 2u32).ln_gamma()
+        - (Float::with_val(work, Constant::Pi) * nu).ln() / 2u32
+        - half * (Float::with_val(work, x.square_ref()) / nu).ln_1p();
+    Ok(log.exp())
+}
+
+fn t_cdf(x: &Float, params: &[Float], work: u32) -> Result<Float, CalcError> {
+    let nu = &params[0];
+    let y = Float::with_val(work, nu / (Float::with_val(work, x.square_ref()) + nu));
+    let tail = special::beta_inc(&Float::with_val(work, nu / 2u32), &Float::with_val(work, 0.5), &y, work)? / 2u32;
+    if *x > 0 {
+        Ok(1u32 - tail)
+    } else { Ok(tail) }
+}
+
+pub fn tpdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    positive(&values[1..])?;
+    result(calc, t_pdf(&values[0], &values[1..], work)?)
+}
+
+pub fn tcdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    positive(&values[1..])?;
+    result(calc, t_cdf(&values[0], &values[1..], work)?)
+}
+
+pub fn tinv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    positive(&values[1..])?;
+    if values[0] <= 0 || values[0] >= 1 {
+        return Err(CalcError::ParameterError);
+    }
+    result(calc, invert(calc, t_cdf, t_pdf, &values[0], &values[1..], None, work)?)
+}
+
+fn chisq_pdf(x: &Float, params: &[Float], work: u32) -> Result<Float, CalcError> {
+    if *x <= 0 {
+        return Ok(Float::with_val(work, 0));
+    }
+    let half = Float::with_val(work, &params[0] / 2u32);
+    let log = Float::with_val(work, &half - 1u32) * Float::with_val(work, x.ln_ref())
+        - Float::with_val(work, x / 2u32)
+        - Float::with_val(work, &half * Float::with_val(work, 2u32).ln())
+        - Float::with_val(work, half.ln_gamma_ref());
+    Ok(log.exp())
+}
+
+fn chisq_cdf(x: &Float, params: &[Float], work: u32) -> Result<Float, CalcError> {
+    if *x <= 0 {
+        return Ok(Float::with_val(work, 0));
+    }
+    special::gamma_p(&Float::with_val(work, &params[0] / 2u32), &Float::with_val(work, x / 2u32), work)
+}
+
+pub fn chisqpdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    positive(&values[1..])?;
+    result(calc, chisq_pdf(&values[0], &values[1..], work)?)
+}
+
+pub fn chisqcdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    positive(&values[1..])?;
+    result(calc, chisq_cdf(&values[0], &values[1..], work)?)
+}
+
+pub fn chisqinv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    positive(&values[1..])?;
+    if values[0] < 0 || values[0] >= 1 {
+        return Err(CalcError::ParameterError);
+    } else if values[0] == 0 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    let lower = Some(Float::with_val(work, 0));
+    result(calc, invert(calc, chisq_cdf, chisq_pdf, &values[0], &values[1..], lower, work)?)
+}
+
+fn f_pdf(x: &Float, params: &[Float], work: u32) -> Result<Float, CalcError> {
+    if *x <= 0 {
+        return Ok(Float::with_val(work, 0));
+    }
+    let (d1, d2) = (&params[0], &params[1]);
+    let (a, b) = (Float::with_val(work, d1 / 2u32), Float::with_val(work, d2 / 2u32));
+    let ratio = Float::with_val(work, d1 * x) / d2;
+    let log = Float::with_val(work, &a * Float::with_val(work, ratio.ln_ref()))
+        - Float::with_val(work, Float::with_val(work, &a + &b) * ratio.ln_1p())
+        - Float::with_val(work, x.ln_ref())
+        - special::ln_beta(&a, &b, work);
+    Ok(log.exp())
+}
+
+fn f_cdf(x: &Float, params: &[Float], work: u32) -> Result<Float, CalcError> {
+    if *x <= 0 {
+        return Ok(Float::with_val(work, 0));
+    }
+    let (d1, d2) = (&params[0], &params[1]);
+    let scaled = Float::with_val(work, d1 * x);
+    let y = Float::with_val(work, &scaled / Float::with_val(work, &scaled + d2));
+    special::beta_inc(&Float::with_val(work, d1 / 2u32), &Float::with_val(work, d2 / 2u32), &y, work)
+}
+
+pub fn fpdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 3..=3)?;
+    positive(&values[1..])?;
+    result(calc, f_pdf(&values[0], &values[1..], work)?)
+}
+
+pub fn fcdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 3..=3)?;
+    positive(&values[1..])?;
+    result(calc, f_cdf(&values[0], &values[1..], work)?)
+}
+
+pub fn finv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 3..=3)?;
+    positive(&values[1..])?;
+    if values[0] < 0 || values[0] >= 1 {
+        return Err(CalcError::ParameterError);
+    } else if values[0] == 0 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    let lower = Some(Float::with_val(work, 0));
+    result(calc, invert(calc, f_cdf, f_pdf, &values[0], &values[1..], lower, work)?)
+}
+
+pub fn exppdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    let (x, rate) = (&values[0], &values[1]);
+    positive(&values[1..])?;
+    if *x < 0 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    result(calc, Float::with_val(work, -Float::with_val(work, rate * x)).exp() * rate)
+}
+
+pub fn expcdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    let (x, rate) = (&values[0], &values[1]);
+    positive(&values[1..])?;
+    if *x < 0 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    result(calc, -Float::with_val(work, -Float::with_val(work, rate * x)).exp_m1())
+}
+
+pub fn expinv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    let (p, rate) = (&values[0], &values[1]);
+    positive(&values[1..])?;
+    if *p < 0 || *p >= 1 {
+        return Err(CalcError::ParameterError);
+    }
+    result(calc, -Float::with_val(work, -p).ln_1p() / rate)
+}
+
+fn binomial(values: &[Float]) -> Result<(), CalcError> {
+    count(&values[1])?;
+    probability(&values[2])
+}
+
+pub fn binompdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 3..=3)?;
+    binomial(&values)?;
+    let (k, n, p) = (&values[0], &values[1], &values[2]);
+    if !k.is_integer() || *k < 0 || k > n {
+        return result(calc, Float::with_val(work, 0));
+    } else if *p == 0 || *p == 1 {
+        let hit = (*p == 0 && *k == 0) || (*p == 1 && k == n);
+        return result(calc, Float::with_val(work, u32::from(hit)));
+    }
+    let rest = Float::with_val(work, n - k);
+    let log = Float::with_val(work, n + 1u32).ln_gamma()
+        - Float::with_val(work, k + 1u32).ln_gamma()
+        - Float::with_val(work, &rest + 1u32).ln_gamma()
+        + Float::with_val(work, k * Float::with_val(work, p.ln_ref()))
+        + rest * Float::with_val(work, -p).ln_1p();
+    result(calc, log.exp())
+}
+
+pub fn binomcdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 3..=3)?;
+    binomial(&values)?;
+    let (k, n, p) = (Float::with_val(work, values[0].floor_ref()), &values[1], &values[2]);
+    if k < 0 {
+        return result(calc, Float::with_val(work, 0));
+    } else if k >= *n || *p == 0 {
+        return result(calc, Float::with_val(work, 1));
+    } else if *p == 1 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    let q = Float::with_val(work, 1u32 - p);
+    result(calc, special::beta_inc(&Float::with_val(work, n - &k), &(k + 1u32), &q, work)?)
+}
+
+pub fn poisspdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    let (k, rate) = (&values[0], &values[1]);
+    positive(&values[1..])?;
+    if !k.is_integer() || *k < 0 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    let log = Float::with_val(work, k * Float::with_val(work, rate.ln_ref()))
+        - rate - Float::with_val(work, k + 1u32).ln_gamma();
+    result(calc, log.exp())
+}
+
+pub fn poisscdf(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (values, work) = params(calc, args, 2..=2)?;
+    let (k, rate) = (Float::with_val(work, values[0].floor_ref()), &values[1]);
+    positive(&values[1..])?;
+    if k < 0 {
+        return result(calc, Float::with_val(work, 0));
+    }
+    result(calc, special::gamma_q(&(k + 1u32), rate, work)?)
+}
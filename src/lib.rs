@@ -1,9 +1,29 @@
-use rug::Float;
+use rug::{Complete, Float, Integer, Rational};
 use rug::ops::Pow;
 use std::cmp::max;
-use rug::float::Constant;
+use std::f64::consts::LOG10_2;
+use rug::float::{Constant, Special};
 use phf::phf_map;
 use phf::Map;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use units::Unit;
+use random::Random;
+
+mod units;
+mod calculus;
+mod series;
+mod stats;
+mod matrix;
+mod poly;
+mod combin;
+mod ntheory;
+mod random;
+mod special;
+mod dist;
+mod logic;
 
 #[derive(Clone)]
 enum Marker {
@@ -37,529 +57,1530 @@ pub enum CalcError {
     OperatorUndefined,
     EmptyExpression,
     InvalidNumber,
+    UnitUndefined,
+    DimensionMismatch,
+    NoConvergence,
+    LimitExceeded,
+    DomainError,
+    DidYouMean(String),
+    DepthExceeded,
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Real(Float),
+    Quantity(Float, Unit),
+    List(Vec<Float>),
+    Matrix(Vec<Vec<Float>>),
+    Complex(Vec<(Float, Float)>),
+    Integer(Integer),
+    Rational(Rational),
+    Factors(Vec<(Integer, u32)>),
 }
 
 type MathFn = fn(Float, &Context) -> Result<Float, CalcError>;
 static MATH: Map<&'static [u8], MathFn> = phf_map! {
-    b"ai" => |v, c| v.ai().accuracy(&c.max),
-    b"li" => |v, c| v.li2().accuracy(&c.max),
-    b"erf" => |v, c| v.erf().accuracy(&c.max),
-    b"erfc" => |v, c| v.erfc().accuracy(&c.max),
-    b"abs" => |v, c| v.abs().accuracy(&c.max),
-    b"ln" => |v, c| if v <= 0.0 {
+    b"ai" => |v, c| v.ai().accuracy(c),
+    b"li2" => |v, c| v.li2().accuracy(c),
+    b"erf" => |v, c| v.erf().accuracy(c),
+    b"erfc" => |v, c| v.erfc().accuracy(c),
+    b"abs" => |v, c| v.abs().accuracy(c),
+    b"ln" => |v, c| if c.strict() && v <= 0.0 {
+        Err(CalcError::ParameterError)
+    } else { v.ln().accuracy(c) },
+    b"exp" => |v, c| v.exp().accuracy(c),
+    b"exp2" => |v, c| v.exp2().accuracy(c),
+    b"exp10" => |v, c| v.exp10().accuracy(c),
+    b"trunc" => |v, c| v.trunc().accuracy(c),
+    b"zeta" => |v, c| if c.strict() && v == 1.0 {
+        Err(CalcError::ParameterError)
+    } else { v.zeta().accuracy(c) },
+    b"gamma" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.ln().accuracy(&c.max) },
-    b"exp" => |v, c| v.exp().accuracy(&c.max),
-    b"expt" => |v, c| v.exp2().accuracy(&c.max),
-    b"expx" => |v, c| v.exp10().accuracy(&c.max),
-    b"trunc" => |v, c| v.trunc().accuracy(&c.max),
-    b"zeta" => |v, c| if v == 1.0 {
+    } else { v.gamma().accuracy(c) },
+    b"lngamma" => |v, c| if c.strict() && (v <= 0.0 && v.is_integer()) {
+        Err(CalcError::DomainError)
+    } else { v.ln_abs_gamma().0.accuracy(c) },
+    b"ellipk" => special::ellipk,
+    b"ellipe" => special::ellipe,
+    b"digamma" => |v, c| if c.strict() && v == 0.0 {
+        Err(CalcError::ParameterError)
+    } else { v.digamma().accuracy(c) },
+    b"eint" => |v, c| if c.strict() && v == 0.0 {
+        Err(CalcError::ParameterError)
+    } else { v.eint().accuracy(c) },
+    b"log" => |v, c| if c.strict() && v <= 0.0 {
+        Err(CalcError::ParameterError)
+    } else {
+        match c.log {
+            LogBase::Binary => v.log2().accuracy(c),
+            LogBase::Natural => v.ln().accuracy(c),
+            LogBase::Common => v.log10().accuracy(c),
+        }
+    },
+    b"log2" => |v, c| if c.strict() && v <= 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.zeta().accuracy(&c.max) },
-    b"gamma" => |v, c| if v == 0.0 {
+    } else { v.log2().accuracy(c) },
+    b"log10" => |v, c| if c.strict() && v <= 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.gamma().accuracy(&c.max) },
-    b"digamma" => |v, c| if v == 0.0 {
+    } else { v.log10().accuracy(c) },
+    b"cos" => |v, c| v.cos().accuracy(c),
+    b"sin" => |v, c| v.sin().accuracy(c),
+    b"tan" => |v, c| v.tan().accuracy(c),
+    b"sec" => |v, c| v.sec().accuracy(c),
+    b"csc" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.digamma().accuracy(&c.max) },
-    b"eint" => |v, c| if v == 0.0 {
+    } else { v.csc().accuracy(c) },
+    b"cot" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.eint().accuracy(&c.max) },
-    b"log" => |v, c| if v <= 0.0 {
+    } else { v.cot().accuracy(c) },
+    b"cosh" => |v, c| v.cosh().accuracy(c),
+    b"sinh" => |v, c| v.sinh().accuracy(c),
+    b"tanh" => |v, c| v.tanh().accuracy(c),
+    b"ceil" => |v, c| v.ceil().accuracy(c),
+    b"floor" => |v, c| v.floor().accuracy(c),
+    b"frac" => |v, c| v.fract().accuracy(c),
+    b"sgn" => |v, c| v.signum().accuracy(c),
+    b"recip" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.log2().accuracy(&c.max) },
-    b"logx" => |v, c| if v <= 0.0 {
+    } else { v.recip().accuracy(c) },
+    b"csch" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.log10().accuracy(&c.max) },
-    b"cos" => |v, c| v.cos().accuracy(&c.max),
-    b"sin" => |v, c| v.sin().accuracy(&c.max),
-    b"tan" => |v, c| v.tan().accuracy(&c.max),
-    b"sec" => |v, c| v.sec().accuracy(&c.max),
-    b"csc" => |v, c| if v == 0.0 {
+    } else { v.csch().accuracy(c) },
+    b"sech" => |v, c| v.sech().accuracy(c),
+    b"coth" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.csc().accuracy(&c.max) },
-    b"cot" => |v, c| if v == 0.0 {
+    } else { v.coth().accuracy(c) },
+    b"acos" => |v, c| if c.strict() && !(-1.0..=1.0).contains(&v) {
         Err(CalcError::ParameterError)
-    } else { v.cot().accuracy(&c.max) },
-    b"cosh" => |v, c| v.cosh().accuracy(&c.max),
-    b"sinh" => |v, c| v.sinh().accuracy(&c.max),
-    b"tanh" => |v, c| v.tanh().accuracy(&c.max),
-    b"ceil" => |v, c| v.ceil().accuracy(&c.max),
-    b"floor" => |v, c| v.floor().accuracy(&c.max),
-    b"frac" => |v, c| v.fract().accuracy(&c.max),
-    b"sgn" => |v, c| v.signum().accuracy(&c.max),
-    b"recip" => |v, c| if v == 0.0 {
+    } else { v.acos().accuracy(c) },
+    b"asin" => |v, c| if c.strict() && !(-1.0..=1.0).contains(&v) {
         Err(CalcError::ParameterError)
-    } else { v.recip().accuracy(&c.max) },
-    b"csch" => |v, c| if v == 0.0 {
+    } else { v.asin().accuracy(c) },
+    b"atan" => |v, c| v.atan().accuracy(c),
+    b"acosh" => |v, c| if c.strict() && v < 1.0 {
         Err(CalcError::ParameterError)
-    } else { v.csch().accuracy(&c.max) },
-    b"sech" => |v, c| v.sech().accuracy(&c.max),
-    b"coth" => |v, c| if v == 0.0 {
+    } else { v.acosh().accuracy(c) },
+    b"asinh" => |v, c| v.asinh().accuracy(c),
+    b"atanh" => |v, c| if c.strict() && (v <= -1.0 || v >= 1.0) {
         Err(CalcError::ParameterError)
-    } else { v.coth().accuracy(&c.max) },
-    b"acos" => |v, c| if v < -1.0 || v > 1.0 {
+    } else { v.atanh().accuracy(c) },
+    b"asec" => |v, c| if c.strict() && (v > -1.0 && v < 1.0) {
         Err(CalcError::ParameterError)
-    } else { v.acos().accuracy(&c.max) },
-    b"asin" => |v, c| if v < -1.0 || v > 1.0 {
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.recip_ref()).acos()).accuracy(c) },
+    b"acsc" => |v, c| if c.strict() && (v > -1.0 && v < 1.0) {
         Err(CalcError::ParameterError)
-    } else { v.asin().accuracy(&c.max) },
-    b"atan" => |v, c| v.atan().accuracy(&c.max),
-    b"acosh" => |v, c| if v < 1.0 {
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.recip_ref()).asin()).accuracy(c) },
+    b"acot" => |v, c| if v == 0.0 {
+        Ok(Float::with_val(c.prec, Constant::Pi) / 2u32)
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.recip_ref()).atan()).accuracy(c) },
+    b"asech" => |v, c| if c.strict() && (v <= 0.0 || v > 1.0) {
         Err(CalcError::ParameterError)
-    } else { v.acosh().accuracy(&c.max) },
-    b"asinh" => |v, c| v.asinh().accuracy(&c.max),
-    b"atanh" => |v, c| if v <= -1.0 || v >= 1.0 {
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.recip_ref()).acosh()).accuracy(c) },
+    b"acsch" => |v, c| if c.strict() && v == 0.0 {
         Err(CalcError::ParameterError)
-    } else { v.atanh().accuracy(&c.max) },
-    b"cbrt" => |v, c| v.cbrt().accuracy(&c.max),
-    b"sqrt" => |v, c| if v < 0.0 {
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.recip_ref()).asinh()).accuracy(c) },
+    b"acoth" => |v, c| if c.strict() && (-1.0..=1.0).contains(&v) {
         Err(CalcError::ParameterError)
-    } else { v.sqrt().accuracy(&c.max) },
-    b"fac" => |v, c| {
-        let to_u32 = v.to_u32_saturating().unwrap();
-        let fac = Float::factorial(to_u32);
-        Float::with_val(c.prec, fac).accuracy(&c.max)
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.recip_ref()).atanh()).accuracy(c) },
+    b"gd" => |v, c| Float::with_val(c.prec, Float::with_val(c.prec + 32, v.sinh_ref()).atan()).accuracy(c),
+    b"not" => |v, _| Ok(Float::with_val(v.prec(), u8::from(v == 0.0))),
+    b"sinc" => |v, c| if v == 0.0 {
+        Ok(Float::with_val(c.prec, 1))
+    } else { Float::with_val(c.prec, Float::with_val(c.prec + 32, v.sin_ref()) / &v).accuracy(c) },
+    b"versin" => |v, c| {
+        let half = Float::with_val(c.prec + 32, &v / 2u32).sin().square() * 2u32;
+        Float::with_val(c.prec, half).accuracy(c)
     },
+    b"cbrt" => |v, c| v.cbrt().accuracy(c),
+    b"sqrt" => |v, c| if c.strict() && v < 0.0 {
+        Err(CalcError::ParameterError)
+    } else { v.sqrt().accuracy(c) },
+};
+
+type SpecialFn = fn(&Calculator, &[&[u8]]) -> Result<Value, CalcError>;
+static SPECIAL: Map<&'static [u8], SpecialFn> = phf_map! {
+    b"diff" => calculus::diff,
+    b"integrate" => calculus::integrate,
+    b"integrate_err" => calculus::integrate_err,
+    b"solve" => calculus::solve,
+    b"sum" => series::sum,
+    b"prod" => series::prod,
+    b"mean" => stats::mean,
+    b"median" => stats::median,
+    b"mode" => stats::mode,
+    b"var" => stats::var,
+    b"pvar" => stats::pvar,
+    b"stdev" => stats::stdev,
+    b"pstdev" => stats::pstdev,
+    b"min" => stats::min,
+    b"max" => stats::max,
+    b"percentile" => stats::percentile,
+    b"geomean" => stats::geomean,
+    b"det" => matrix::det,
+    b"inv" => matrix::inv,
+    b"transpose" => matrix::transpose,
+    b"dot" => matrix::dot,
+    b"cross" => matrix::cross,
+    b"norm" => matrix::norm,
+    b"rank" => matrix::rank,
+    b"roots" => poly::roots,
+    b"polyval" => poly::polyval,
+    b"polyder" => poly::polyder,
+    b"polyfit" => poly::polyfit,
+    b"fac" => combin::fac,
+    b"ncr" => combin::ncr,
+    b"npr" => combin::npr,
+    b"multinomial" => combin::multinomial,
+    b"subfactorial" => combin::subfactorial,
+    b"catalan" => combin::catalan,
+    b"stirling" => combin::stirling,
+    b"stirlingf" => combin::stirlingf,
+    b"bell" => combin::bell,
+    b"if" => logic::condition,
+    b"clamp" => logic::clamp,
+    b"approx" => logic::approx,
+    b"mod" => ntheory::modulo,
+    b"rem" => ntheory::rem,
+    b"emod" => ntheory::emod,
+    b"gcd" => ntheory::gcd,
+    b"lcm" => ntheory::lcm,
+    b"isprime" => ntheory::isprime,
+    b"nextprime" => ntheory::nextprime,
+    b"prevprime" => ntheory::prevprime,
+    b"factor" => ntheory::factor,
+    b"totient" => ntheory::totient,
+    b"modpow" => ntheory::modpow,
+    b"modinv" => ntheory::modinv,
+    b"jacobi" => ntheory::jacobi,
+    b"rand" => random::rand,
+    b"randint" => random::randint,
+    b"randn" => random::randn,
+    b"choose" => random::choose,
+    b"normpdf" => dist::normpdf,
+    b"normcdf" => dist::normcdf,
+    b"norminv" => dist::norminv,
+    b"tpdf" => dist::tpdf,
+    b"tcdf" => dist::tcdf,
+    b"tinv" => dist::tinv,
+    b"chisqpdf" => dist::chisqpdf,
+    b"chisqcdf" => dist::chisqcdf,
+    b"chisqinv" => dist::chisqinv,
+    b"fpdf" => dist::fpdf,
+    b"fcdf" => dist::fcdf,
+    b"finv" => dist::finv,
+    b"exppdf" => dist::exppdf,
+    b"expcdf" => dist::expcdf,
+    b"expinv" => dist::expinv,
+    b"binompdf" => dist::binompdf,
+    b"binomcdf" => dist::binomcdf,
+    b"poisspdf" => dist::poisspdf,
+    b"poisscdf" => dist::poisscdf,
+    b"besselj" => special::besselj,
+    b"bessely" => special::bessely,
+    b"agm" => special::agm,
+    b"hypot" => special::hypot,
+    b"beta" => special::beta,
+    b"gammainc" => special::gammainc,
+    b"lambertw" => special::lambertw,
+};
+
+static ALIASES: Map<&'static [u8], &'static [u8]> = phf_map! {
+    b"li" => b"li2",
+    b"expt" => b"exp2",
+    b"expx" => b"exp10",
+    b"logx" => b"log10",
+    b"lg" => b"log10",
+    b"lb" => b"log2",
+    b"arcsin" => b"asin",
+    b"arccos" => b"acos",
+    b"arctan" => b"atan",
+    b"arcsec" => b"asec",
+    b"arccsc" => b"acsc",
+    b"arccot" => b"acot",
+    b"arsinh" => b"asinh",
+    b"arcosh" => b"acosh",
+    b"artanh" => b"atanh",
+    b"arcsinh" => b"asinh",
+    b"arccosh" => b"acosh",
+    b"arctanh" => b"atanh",
+    b"cosec" => b"csc",
+    b"sign" => b"sgn",
+    b"ceiling" => b"ceil",
+    b"tgamma" => b"gamma",
+    b"lgamma" => b"lngamma",
+    b"gammaln" => b"lngamma",
+    b"psi" => b"digamma",
+    b"Ei" => b"eint",
+    b"factorial" => b"fac",
+    b"binomial" => b"ncr",
+    b"nCr" => b"ncr",
+    b"nPr" => b"npr",
+    b"avg" => b"mean",
+    b"std" => b"stdev",
+    b"hypotenuse" => b"hypot",
+    b"fmod" => b"rem",
+    b"isclose" => b"approx",
 };
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogBase {
+    #[default]
+    Binary,
+    Natural,
+    Common,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Magnitude {
+    #[default]
+    Capped,
+    Extended,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    #[default]
+    Strict,
+    Ieee,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Numbers {
+    #[default]
+    Binary,
+    Decimal,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Percent {
+    #[default]
+    Remainder,
+    Percentage,
+}
+
+#[derive(Clone, Debug)]
+pub struct Statement {
+    pub line: usize,
+    pub name: Option<String>,
+    pub result: Result<Value, CalcError>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Limits {
+    pub bits: u64,
+    pub factorial: u32,
+    pub evaluations: u64,
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self { bits: 1 << 24, factorial: 100_000, evaluations: 10_000_000, timeout: None }
+    }
+}
+
 #[derive(Clone)]
 struct Context {
     pub max: Float,
     pub prec: u32,
+    pub log: LogBase,
+    pub magnitude: Magnitude,
+    pub arithmetic: Arithmetic,
+    pub numbers: Numbers,
+    pub percent: Percent,
+    pub depth: usize,
+    pub limits: Limits,
+    // Cleared at the start of every top-level `run`, so it only aborts the evaluation in progress.
+    cancel: Arc<AtomicBool>,
+    steps: Arc<AtomicU64>,
+    deadline: Option<Instant>,
 }
 
+/// Clones share one random generator, so draws in either advance the same stream.
 #[derive(Clone)]
 pub struct Calculator {
     marker: Marker,
     context: Context,
     operator: Vec<u8>,
     function: Vec<Option<MathFn>>,
-    numbers: Vec<Float>,
-    bracket: usize,
+    numbers: Vec<Value>,
+    variables: Vec<(Vec<u8>, Value)>,
+    random: Arc<Mutex<Random>>,
+    mark: u64,
+    level: usize,
     state: State,
 }
 
-fn max_value(prec: u32) -> Float {
+const MAX_DEPTH: usize = 256;
+
+fn max_value(prec: u32, magnitude: Magnitude) -> Float {
+    if magnitude == Magnitude::Extended {
+        return Float::with_val(prec, Special::Infinity);
+    }
     let k = (prec as f64 * 0.0025).floor() as u32;
-    let d = (prec as f64 * 0.3010299956639812).floor() as u32;
+    let d = (prec as f64 * LOG10_2).floor() as u32;
     let max_val = Float::i_pow_u(10, d-k);
     Float::with_val(prec, max_val)
 }
 
+fn number_end(expr: &[u8], start: usize) -> usize {
+    let mut index = start;
+    while matches!(expr.get(index), Some(b'0'..=b'9' | b'.')) {
+        index += 1;
+    }
+    if matches!(expr.get(index), Some(b'e' | b'E')) {
+        let mut exp = index + 1;
+        if matches!(expr.get(exp), Some(b'+' | b'-')) {
+            exp += 1;
+        }
+        if matches!(expr.get(exp), Some(b'0'..=b'9')) {
+            index = exp;
+            while matches!(expr.get(index), Some(b'0'..=b'9')) {
+                index += 1;
+            }
+        }
+    }
+    index
+}
+
+fn word_end(expr: &[u8], start: usize) -> usize {
+    let mut index = start;
+    while expr.get(index).is_some_and(|&b| b.is_ascii_alphanumeric() || b == b'_') {
+        index += 1;
+    }
+    index
+}
+
+fn arguments(expr: &[u8], open: usize) -> Result<(Vec<&[u8]>, usize), CalcError> {
+    let mut depth = 0;
+    let mut start = open + 1;
+    let mut args = Vec::new();
+    for (index, &byte) in expr.iter().enumerate().skip(open) {
+        match byte {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    args.push(&expr[start..index]);
+                    return Ok((args, index + 1));
+                }
+            },
+            b',' if depth == 1 => {
+                args.push(&expr[start..index]);
+                start = index + 1;
+            },
+            b'=' if terminal(expr, index) => break,
+            b'\n' | b'\r' => break,
+            _ => {},
+        }
+    }
+    Err(CalcError::ExpressionError)
+}
+
+fn terminal(expr: &[u8], index: usize) -> bool {
+    expr.get(index + 1) != Some(&b'=')
+        && !matches!(index.checked_sub(1).and_then(|i| expr.get(i)), Some(b'<' | b'>' | b'=' | b'!'))
+}
+
+fn identifier(expr: &[u8]) -> Result<&[u8], CalcError> {
+    let name = expr.trim_ascii();
+    if name.first().is_some_and(u8::is_ascii_alphabetic) && word_end(name, 0) == name.len() {
+        Ok(name)
+    } else { Err(CalcError::ParameterError) }
+}
+
+fn canonical(name: &[u8]) -> &[u8] {
+    ALIASES.get(name).copied().unwrap_or(name)
+}
+
+fn distance(a: &[u8], b: &[u8]) -> usize {
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, &x) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let cost = diag + usize::from(x != y);
+            diag = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(diag + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn suggest(name: &[u8]) -> CalcError {
+    let limit = (name.len() / 3).clamp(1, 2);
+    let names = MATH.keys().chain(SPECIAL.keys()).map(|key| (key, false));
+    names.chain(ALIASES.keys().map(|key| (key, true)))
+        .map(|(key, alias)| (distance(name, key), alias, key.len(), *key))
+        .filter(|&(d, ..)| d <= limit)
+        .min()
+        .map_or(CalcError::FunctionUndefined, |(.., key)| {
+            CalcError::DidYouMean(String::from_utf8_lossy(key).into_owned())
+        })
+}
+
 fn extract(expr: &[u8], c: &Context, n: usize, i: usize) -> Result<Float, CalcError> {
     match Float::parse(&expr[n..i]) {
-        Ok(valid) => Float::with_val(c.prec, valid).accuracy(&c.max),
+        Ok(valid) => Float::with_val(c.prec, valid).accuracy(c),
         Err(_) => Err(CalcError::InvalidNumber)
     }
 }
 
+fn decimal(expr: &[u8], c: &Context) -> Result<Value, CalcError> {
+    let text = std::str::from_utf8(expr).map_err(|_| CalcError::InvalidNumber)?;
+    let (mantissa, exp) = text.split_once(['e', 'E']).unwrap_or((text, "0"));
+    let (whole, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && frac.is_empty() || frac.contains('.') {
+        return Err(CalcError::InvalidNumber);
+    }
+    let digits = Integer::from_str_radix(&[whole, frac].concat(), 10).map_err(|_| CalcError::InvalidNumber)?;
+    let exp = exp.parse::<i64>().map_err(|_| CalcError::InvalidNumber)? - frac.len() as i64;
+    let power = u32::try_from(exp.unsigned_abs()).ok()
+        .filter(|&n| f64::from(n) / LOG10_2 <= c.limits.bits as f64)
+        .ok_or(CalcError::LimitExceeded)?;
+    let power = Integer::u_pow_u(10, power).complete();
+    if exp >= 0 {
+        Ok(Value::Integer(digits * power))
+    } else { Ok(Value::rational(Rational::from((digits, power)))) }
+}
+
 trait ByteExt {
     fn priority(&self) -> Result<u8, CalcError>;
-    fn computing(&self, n: &mut Calculator) -> Result<Float, CalcError>;
+    fn arithmetic(&self, c2: &Float, c1: &Float, c: &Context) -> Result<Float, CalcError>;
+    fn dimension(&self, c2: &Unit, c1: &Unit, n: &Float) -> Result<Unit, CalcError>;
+    fn exact(&self, c2: &Integer, c1: &Integer, context: &Context) -> Result<Integer, CalcError>;
+    fn ratio(&self, c2: &Rational, c1: &Rational, context: &Context) -> Result<Option<Rational>, CalcError>;
+    fn computing(&self, n: &mut Calculator) -> Result<Value, CalcError>;
+    fn logical(&self, c2: Value, c1: Value, context: &Context) -> Result<Value, CalcError>;
 }
 
 trait FloatExt {
     fn fmod(&self, n: &Float, prec: u32) -> Float;
-    fn accuracy(self, n: &Float) -> Result<Float, CalcError>;
+    fn accuracy(self, context: &Context) -> Result<Float, CalcError>;
     fn to_round(&self, digits: Option<usize>) -> Result<String, CalcError>;
-}
-
-trait StringExt {
-    fn parse_rug_raw(&self) -> (bool, Vec<u8>, i32);
-    fn to_fixed_clean(&self) -> Result<String, CalcError>;
-    fn to_fixed_round(&self, prec: i32) -> Result<String, CalcError>;
+    fn write_round<W: fmt::Write>(&self, out: &mut W, digits: Option<usize>) -> Result<(), CalcError>;
+    fn write_scientific<W: fmt::Write>(&self, out: &mut W, digits: Option<usize>) -> Result<(), CalcError>;
 }
 
 impl ByteExt for u8 {
     fn priority(&self) -> Result<u8, CalcError> {
         match self {
-            b'+' | b'-' => Ok(1),
-            b'*' | b'/' | b'%' => Ok(2),
-            b'^' => Ok(3),
+            b'|' => Ok(1),
+            b'&' => Ok(2),
+            b'<' | b'>' | b'{' | b'}' | b'=' | b'!' => Ok(3),
+            b'+' | b'-' => Ok(4),
+            b'*' | b'/' | b'%' | b'\\' => Ok(5),
+            b'_' => Ok(6),
+            b'^' => Ok(7),
+            _ => Err(CalcError::UnknownOperator)
+        }
+    }
+
+    fn arithmetic(&self, c2: &Float, c1: &Float, context: &Context) -> Result<Float, CalcError> {
+        match self {
+            b'+' => Float::with_val(context.prec, c2 + c1).accuracy(context),
+            b'-' => Float::with_val(context.prec, c2 - c1).accuracy(context),
+            b'*' => Float::with_val(context.prec, c2 * c1).accuracy(context),
+            b'/' if *c1 != 0.0 || !context.strict() => Float::with_val(context.prec, c2 / c1).accuracy(context),
+            b'%' if *c1 != 0.0 || !context.strict() => c2.fmod(c1, context.prec).accuracy(context),
+            b'\\' if *c1 != 0.0 || !context.strict() => {
+                let r = c2.fmod(c1, context.prec);
+                let q = Float::with_val(context.prec, Float::with_val(context.prec, c2 - &r) / c1).round();
+                if r != 0.0 && r.is_sign_negative() != c1.is_sign_negative() {
+                    (q - 1u32).accuracy(context)
+                } else { q.accuracy(context) }
+            },
+            b'^' => Float::with_val(context.prec, c2.pow(c1)).accuracy(context),
+            _ => Err(CalcError::DivideByZero)
+        }
+    }
+
+    fn dimension(&self, c2: &Unit, c1: &Unit, exp: &Float) -> Result<Unit, CalcError> {
+        if c2.is_affine() || c1.is_affine() {
+            return match self {
+                b'-' if c2.is_affine() && c1.is_affine() => Ok(c2.base()),
+                _ => Err(CalcError::DimensionMismatch),
+            };
+        }
+        match self {
+            b'+' | b'-' | b'%' if c2.same_dims(c1) => {
+                if c2.is_dimensionless() {
+                    Ok(c1.clone())
+                } else { Ok(c2.clone()) }
+            },
+            b'*' => c2.mul(c1),
+            b'/' | b'\\' => c2.div(c1),
+            b'^' if c1.is_dimensionless() => {
+                if c2.is_dimensionless() {
+                    return Ok(c2.clone());
+                } else if !exp.is_integer() {
+                    return Err(CalcError::DimensionMismatch);
+                }
+                let n = exp.to_i32_saturating().unwrap_or(0);
+                c2.pow(i8::try_from(n).map_err(|_| CalcError::DimensionMismatch)?)
+            },
+            _ => Err(CalcError::DimensionMismatch)
+        }
+    }
+
+    fn exact(&self, c2: &Integer, c1: &Integer, context: &Context) -> Result<Integer, CalcError> {
+        match self {
+            b'+' => Ok(Integer::from(c2 + c1)),
+            b'-' => Ok(Integer::from(c2 - c1)),
+            b'*' => Ok(Integer::from(c2 * c1)),
+            b'\\' => Ok(c2.div_rem_floor_ref(c1).complete().0),
+            b'^' => match c1.to_u32() {
+                Some(exp) if u64::from(c2.significant_bits()) * u64::from(exp) <= context.limits.bits => {
+                    Ok(Integer::from(c2.pow(exp)))
+                },
+                _ => Err(CalcError::LimitExceeded),
+            },
             _ => Err(CalcError::UnknownOperator)
         }
     }
 
-    fn computing(&self, num: &mut Calculator) -> Result<Float, CalcError> {
+    fn ratio(&self, c2: &Rational, c1: &Rational, context: &Context) -> Result<Option<Rational>, CalcError> {
+        match self {
+            b'+' => Ok(Some(Rational::from(c2 + c1))),
+            b'-' => Ok(Some(Rational::from(c2 - c1))),
+            b'*' => Ok(Some(Rational::from(c2 * c1))),
+            b'/' if *c1 != 0 => Ok(Some(Rational::from(c2 / c1))),
+            b'%' if *c1 != 0 => {
+                Ok(Some(c2 - Rational::from(c2 / c1).trunc() * c1))
+            },
+            b'\\' if *c1 != 0 => Ok(Some(Rational::from(c2 / c1).floor())),
+            b'^' if c1.is_integer() && *c2 == 0 && *c1 < 0 => {
+                if context.strict() { Err(CalcError::DivideByZero) } else { Ok(None) }
+            },
+            b'^' if c1.is_integer() => {
+                let bits = u64::from(c2.numer().significant_bits() + c2.denom().significant_bits());
+                match c1.numer().to_i32() {
+                    Some(exp) if bits * u64::from(exp.unsigned_abs()) <= context.limits.bits => {
+                        Ok(Some(Rational::from(c2.pow(exp))))
+                    },
+                    _ => Err(CalcError::LimitExceeded),
+                }
+            },
+            _ => Ok(None)
+        }
+    }
+
+    fn computing(&self, num: &mut Calculator) -> Result<Value, CalcError> {
+        if *self == b'_' {
+            return b'*'.computing(num);
+        }
         let context = &num.context;
         let c1 = num.numbers.pop().ok_or(CalcError::ExpressionError)?;
         let c2 = num.numbers.pop().ok_or(CalcError::ExpressionError)?;
-        match self {
-            b'+' => Float::with_val(context.prec, &c2 + &c1).accuracy(&context.max),
-            b'-' => Float::with_val(context.prec, &c2 - &c1).accuracy(&context.max),
-            b'*' => Float::with_val(context.prec, &c2 * &c1).accuracy(&context.max),
-            b'/' if &c1 != &0.0 => Float::with_val(context.prec, &c2 / &c1).accuracy(&context.max),
-            b'%' if &c1 != &0.0 => c2.fmod(&c1, context.prec).accuracy(&context.max),
-            b'^' => Float::with_val(context.prec, &c2.pow(&c1)).accuracy(&context.max),
-            _ => Err(CalcError::DivideByZero)
+        if matches!(self, b'<' | b'>' | b'{' | b'}' | b'=' | b'!' | b'&' | b'|') {
+            return self.logical(c2, c1, context);
         }
+        if let (Value::Integer(c2), Value::Integer(c1)) = (&c2, &c1) {
+            if matches!(self, b'+' | b'-' | b'*') || (*self == b'^' && *c1 >= 0) || (*self == b'\\' && *c1 != 0) {
+                return Ok(Value::Integer(self.exact(c2, c1, context)?));
+            } else if *self == b'/' && *c1 != 0 {
+                let value = Value::rational(Rational::from((c2, c1)));
+                return if context.decimal() { Ok(value) } else { value.widen(context) };
+            }
+        }
+        if context.decimal() {
+            if let (Some(c2), Some(c1)) = (c2.fraction(), c1.fraction()) {
+                if let Some(value) = self.ratio(&c2, &c1, context)? {
+                    return Ok(Value::rational(value));
+                }
+            }
+        }
+        match (c2.widen(context)?, c1.widen(context)?) {
+            (Value::Real(c2), Value::Real(c1)) => {
+                Ok(Value::Real(self.arithmetic(&c2, &c1, context)?))
+            },
+            (c2, c1) if c2.is_array() || c1.is_array() => {
+                matrix::operate(*self, c2, c1, context)
+            },
+            (c2, c1) => {
+                let (c2, u2) = c2.split(context)?;
+                let (c1, u1) = c1.split(context)?;
+                let unit = self.dimension(&u2, &u1, &c1)?;
+                let value = self.arithmetic(&c2, &c1, context)?;
+                Ok(Value::quantity(value, unit))
+            }
+        }
+    }
+
+    fn logical(&self, c2: Value, c1: Value, context: &Context) -> Result<Value, CalcError> {
+        let truth = match self {
+            b'&' => c2.truthy(context)? & c1.truthy(context)?,
+            b'|' => c2.truthy(context)? | c1.truthy(context)?,
+            op => {
+                let order = c2.compare(c1, context)?;
+                match op {
+                    b'<' => order.is_some_and(|o| o.is_lt()),
+                    b'{' => order.is_some_and(|o| o.is_le()),
+                    b'>' => order.is_some_and(|o| o.is_gt()),
+                    b'}' => order.is_some_and(|o| o.is_ge()),
+                    b'=' => order.is_some_and(|o| o.is_eq()),
+                    _ => order.is_none_or(|o| o.is_ne()),
+                }
+            }
+        };
+        Ok(Value::boolean(truth))
     }
 }
 
 impl FloatExt for Float {
     fn fmod(&self, n: &Float, prec: u32) -> Float {
-        let mut m = Float::with_val(prec, self / n);
-        if self < &0.0 {
-            m.ceil_mut()
-        } else { m.floor_mut() };
-        Float::with_val(prec, self - &m * n)
+        Float::with_val(prec, self % n)
     }
 
-    fn accuracy(self, max: &Float) -> Result<Float, CalcError> {
-        if self.is_nan() || self.is_infinite() {
-            Err(CalcError::BeyondAccuracy)
-        } else if self > *max || self < *max.as_neg() {
+    fn accuracy(self, context: &Context) -> Result<Float, CalcError> {
+        let max = &context.max;
+        if !context.strict() && (self.is_nan() || self.is_infinite()) {
+            Ok(self)
+        } else if self.is_nan() || self.is_infinite() || self > *max || self < *max.as_neg() {
             Err(CalcError::BeyondAccuracy)
         } else { Ok(self) }
     }
 
     fn to_round(&self, digits: Option<usize>) -> Result<String, CalcError> {
-        if let Some(precision) = digits {
-            if precision < 1 || precision > 700 {
-                let err = String::from("Set Precision Greater Than Equal 1");
-                return Err(CalcError::Custom(err));
-            }
+        let mut out = String::new();
+        self.write_round(&mut out, digits)?;
+        Ok(out)
+    }
+
+    fn write_round<W: fmt::Write>(&self, out: &mut W, digits: Option<usize>) -> Result<(), CalcError> {
+        if digits == Some(0) {
+            let err = String::from("Set Precision Greater Than Equal 1");
+            return Err(CalcError::Custom(err));
         }
-        let raw = self.to_string_radix(10, None);
-        match digits {
-            None => raw.to_fixed_clean(),
-            Some(digits) => raw.to_fixed_round(digits as i32),
-        }
-    }
-}
-
-impl StringExt for String {
-    fn parse_rug_raw(&self) -> (bool, Vec<u8>, i32) {
-        let bytes = self.as_bytes();
-        let is_neg = bytes.starts_with(&[b'-']);
-        let start = if is_neg { 1 } else { 0 };
-        let e_pos = bytes.iter().position(|&b| b == b'e');
-        let end = e_pos.unwrap_or(bytes.len());
-        let mantissa = &bytes[start..end];
-        let mut digits = Vec::with_capacity(mantissa.len());
-        let mut dot_pos = None;
-        for (index, &byte) in mantissa.iter().enumerate() {
-            if byte == b'.' {
-                dot_pos = Some(index as i32);
-            } else { digits.push(byte); }
-        }
-        let raw_exp: i32 = e_pos.map(|pos|{
-            self[pos+1..].parse().unwrap_or(0)
-        }).unwrap_or(0);
-        let adj_exp = dot_pos.map(|pos| raw_exp+pos)
-        .unwrap_or(raw_exp+digits.len() as i32);
-        (is_neg, digits, adj_exp)
-    }
-
-    fn to_fixed_clean(&self) -> Result<String, CalcError> {
-        let (negative, digits, exp) = self.parse_rug_raw();
-        let mut cursor = 0;
-        let digits_len = digits.len();
-        let exp_abs = (exp.unsigned_abs()+2) as usize;
-        let mut buf = vec![b'0'; digits_len+exp_abs];
-        if negative {
-            buf[cursor] = b'-';
-            cursor += 1;
-        }
-        let dot_pos: Option<usize>;
-        if exp <= 0 {
-            buf[cursor..cursor+2]
-            .copy_from_slice(b"0.");
-            dot_pos = Some(cursor+1);
-            cursor += 2;
-            let zeros = exp.abs() as usize;
-            cursor += zeros;
-            buf[cursor..cursor+digits_len]
-            .copy_from_slice(&digits);
-            cursor += digits_len;
-        } else {
-            let dot_idx = exp as usize;
-            if dot_idx >= digits_len {
-                buf[cursor..cursor+digits_len]
-                .copy_from_slice(&digits);
-                cursor += digits_len;
-                let trailing_zeros = dot_idx-digits_len;
-                cursor += trailing_zeros;
-                dot_pos = None;
-            } else {
-                buf[cursor..cursor+dot_idx]
-                .copy_from_slice(&digits[..dot_idx]);
-                cursor += dot_idx;
-                buf[cursor] = b'.';
-                dot_pos = Some(cursor);
-                cursor += 1;
-                let rem = digits_len-dot_idx;
-                buf[cursor..cursor+rem]
-                .copy_from_slice(&digits[dot_idx..]);
-                cursor += rem;
-            }
+        if self.is_nan() {
+            return Ok(out.write_str("NaN")?);
+        } else if self.is_infinite() {
+            return Ok(out.write_str(if self.is_sign_negative() { "-inf" } else { "inf" })?);
+        } else if self.is_zero() {
+            return Ok(out.write_char('0')?);
         }
-        let mut final_len = cursor;
-        if let Some(dot) = dot_pos {
-            let dec_len = cursor-dot;
-            final_len = if dec_len < 700
-            { dec_len } else { 700 };
-            while final_len > 0 {
-                match buf[final_len-1] {
-                    b'0' => final_len -= 1,
-                    b'.' => { final_len -= 1; break; }
-                    _ => break,
-                }
-            }
+        let (mantissa, exp) = self.to_integer_exp().ok_or(CalcError::BeyondAccuracy)?;
+        if self.get_exp().is_some_and(|bits| bits > 0 && bits.unsigned_abs() > self.prec()) {
+            return self.write_scientific(out, digits);
         }
-        buf.truncate(final_len);
-        Ok(String::from_utf8(buf).unwrap())
-    }
-
-    fn to_fixed_round(&self, prec: i32) -> Result<String, CalcError> {
-        let (negative, digits, exp) = self.parse_rug_raw();
-        let round_idx = (exp+prec) as usize;
-        let mut carry = false;
-        if round_idx < digits.len() && digits[round_idx] >= b'5' {
-            carry = true;
-        }
-        let mut buf = [b'0'; 4096];
-        let mut cursor = 4095;
-        let max_bound = (exp+prec)-1;
-        let min_bound = std::cmp::min(0, exp-1);
-        for index in (min_bound..=max_bound).rev() {
-            if index == exp-1 && prec > 0 {
-                buf[cursor] = b'.';
-                cursor -= 1;
-            }
-            let index = index as usize;
-            let mut digit = if index < digits.len() {
-                digits[index]
-            } else { b'0' };
-            if carry {
-                if digit == b'9' {
-                    digit = b'0';
-                } else {
-                    digit += 1;
-                    carry = false;
-                }
+        let exp = i64::from(exp);
+        let (mut shift, significant) = match digits {
+            Some(digits) => (i64::try_from(digits).unwrap_or(i64::MAX), None),
+            None => {
+                let significant = (f64::from(self.prec()) * LOG10_2).floor() as i64;
+                let leading = (f64::from(self.get_exp().unwrap_or(1) - 1) * LOG10_2).floor() as i64;
+                (significant - leading - 1, Some(significant))
+            },
+        };
+        shift = shift.min(max(0, -exp));
+        let mut scaled = fixed(&mantissa, exp, shift)?;
+        if let Some(significant) = significant.and_then(|n| u32::try_from(n).ok()) {
+            if scaled >= Integer::u_pow_u(10, significant).complete() {
+                shift -= 1;
+                scaled = fixed(&mantissa, exp, shift)?;
             }
-            buf[cursor] = digit;
-            cursor -= 1;
-        }
-        if carry {
-            buf[cursor] = b'1';
-            cursor -= 1;
-        }
-        if negative {
-            buf[cursor] = b'-';
-            cursor -= 1;
-        }
-        let final_buf = &buf[cursor+1..4096];
-        let mut final_len = final_buf.len();
-        for index in final_buf.iter().rev() {
-            match index {
-                b'0' => final_len -= 1,
-                b'.' => { final_len -= 1; break; }
-                _ => break,
+        }
+        write_fixed(out, &scaled, mantissa < 0, shift)
+    }
+
+    fn write_scientific<W: fmt::Write>(&self, out: &mut W, digits: Option<usize>) -> Result<(), CalcError> {
+        let trusted = (f64::from(self.prec()) * LOG10_2).floor() as u32;
+        let shown = digits.and_then(|digits| u32::try_from(digits).ok())
+            .map_or(trusted, |digits| trusted.min(digits.saturating_add(1)));
+        let work = self.prec() + 64;
+        let mut exp = (f64::from(self.get_exp().unwrap_or(1) - 1) * LOG10_2).floor() as i32;
+        let limit = Integer::u_pow_u(10, shown).complete();
+        let scaled = loop {
+            let scale = Float::with_val(work, 10).pow(exp + 1 - shown as i32);
+            let scaled = (Float::with_val(work, self.abs_ref()) / scale).round().to_integer()
+                .ok_or(CalcError::BeyondAccuracy)?;
+            if scaled < limit {
+                break scaled;
             }
+            exp += 1;
+        };
+        write_fixed(out, &scaled, self.is_sign_negative(), i64::from(shown) - 1)?;
+        Ok(write!(out, "e{} ({} digits)", exp, trusted)?)
+    }
+}
+
+fn fixed(mantissa: &Integer, exp: i64, shift: i64) -> Result<Integer, CalcError> {
+    let bits = |n: i64| u32::try_from(n.unsigned_abs()).map_err(|_| CalcError::LimitExceeded);
+    let mut num = mantissa.clone().abs();
+    let mut den = Integer::from(1);
+    if exp >= 0 {
+        num <<= bits(exp)?;
+    } else { den <<= bits(exp)?; }
+    scale(num, den, shift)
+}
+
+fn scale(mut num: Integer, mut den: Integer, shift: i64) -> Result<Integer, CalcError> {
+    let power = |n: i64| u32::try_from(n.unsigned_abs()).map(|n| Integer::u_pow_u(10, n).complete())
+        .map_err(|_| CalcError::LimitExceeded);
+    if shift >= 0 {
+        num *= power(shift)?;
+    } else { den *= power(shift)?; }
+    Ok((num * 2u32 + &den) / (den * 2u32))
+}
+
+fn write_ratio<W: fmt::Write>(out: &mut W, value: &Rational, digits: Option<usize>) -> Result<(), CalcError> {
+    if digits == Some(0) {
+        let err = String::from("Set Precision Greater Than Equal 1");
+        return Err(CalcError::Custom(err));
+    }
+    let mut rest = value.denom().clone();
+    let twos = rest.remove_factor_mut(&Integer::from(2));
+    let fives = rest.remove_factor_mut(&Integer::from(5));
+    let shift = match digits {
+        Some(digits) if rest == 1 => i64::from(max(twos, fives)).min(i64::try_from(digits).unwrap_or(i64::MAX)),
+        Some(digits) => i64::try_from(digits).unwrap_or(i64::MAX),
+        None if rest == 1 => i64::from(max(twos, fives)),
+        None => return Ok(write!(out, "{}", value)?),
+    };
+    let scaled = scale(value.numer().clone().abs(), value.denom().clone(), shift)?;
+    write_fixed(out, &scaled, *value < 0, shift)
+}
+
+fn write_list<W: fmt::Write>(out: &mut W, items: &[Float], digits: Option<usize>) -> Result<(), CalcError> {
+    out.write_char('[')?;
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            out.write_str(", ")?;
         }
-        let result = final_buf[..final_len].to_vec();
-        Ok(String::from_utf8(result).unwrap())
+        item.write_round(out, digits)?;
     }
+    Ok(out.write_char(']')?)
+}
+
+fn write_fixed<W: fmt::Write>(out: &mut W, scaled: &Integer, negative: bool, shift: i64) -> Result<(), CalcError> {
+    if *scaled == 0 {
+        out.write_char('0')?;
+        return Ok(());
+    } else if negative {
+        out.write_char('-')?;
+    }
+    if shift <= 0 {
+        write!(out, "{}", scaled)?;
+        for _ in 0..shift.unsigned_abs() {
+            out.write_char('0')?;
+        }
+        return Ok(());
+    }
+    let scale = Integer::u_pow_u(10, u32::try_from(shift).map_err(|_| CalcError::LimitExceeded)?).complete();
+    let (whole, mut frac) = scaled.div_rem_ref(&scale).complete();
+    write!(out, "{}", whole)?;
+    if frac != 0 {
+        let mut width = shift as usize;
+        while frac.is_divisible_u(10) {
+            frac /= 10u32;
+            width -= 1;
+        }
+        write!(out, ".{:0>width$}", frac)?;
+    }
+    Ok(())
 }
 
 impl Context {
     fn new(prec: u32) -> Self {
-        let max = max_value(prec);
-        Self { prec, max }
+        let max = max_value(prec, Magnitude::default());
+        Self {
+            prec, max,
+            log: LogBase::default(),
+            magnitude: Magnitude::default(),
+            arithmetic: Arithmetic::default(),
+            numbers: Numbers::default(),
+            percent: Percent::default(),
+            depth: MAX_DEPTH,
+            limits: Limits::default(),
+            cancel: Arc::new(AtomicBool::new(false)),
+            steps: Arc::new(AtomicU64::new(0)),
+            deadline: None,
+        }
+    }
+
+    fn strict(&self) -> bool {
+        self.arithmetic == Arithmetic::Strict
+    }
+
+    fn decimal(&self) -> bool {
+        self.numbers == Numbers::Decimal
+    }
+
+    fn check(&self) -> Result<(), CalcError> {
+        if self.cancel.load(Ordering::Relaxed) {
+            Err(CalcError::Cancelled)
+        } else if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Err(CalcError::LimitExceeded)
+        } else { Ok(()) }
+    }
+}
+
+impl From<fmt::Error> for CalcError {
+    fn from(_: fmt::Error) -> Self {
+        CalcError::UnknownError
     }
 }
 
-impl CalcError {
-    pub fn to_string(&self) -> String {
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            CalcError::UnknownOperator => String::from("Unknown Operator"),
-            CalcError::Custom(error) => String::from(error),
-            CalcError::DivideByZero => String::from("Divide By Zero"),
-            CalcError::BeyondAccuracy => String::from("Beyond Accuracy"),
-            CalcError::UnknownError => String::from("Unknown Error"),
-            CalcError::ParameterError => String::from("Parameter Error"),
-            CalcError::ExpressionError => String::from("Expression Error"),
-            CalcError::FunctionUndefined => String::from("Function Undefined"),
-            CalcError::OperatorUndefined => String::from("Operator Undefined"),
-            CalcError::EmptyExpression => String::from("Empty Expression"),
-            CalcError::InvalidNumber => String::from("Invalid Number"),
+            CalcError::UnknownOperator => write!(f, "Unknown Operator"),
+            CalcError::Custom(error) => write!(f, "{}", error),
+            CalcError::DivideByZero => write!(f, "Divide By Zero"),
+            CalcError::BeyondAccuracy => write!(f, "Beyond Accuracy"),
+            CalcError::UnknownError => write!(f, "Unknown Error"),
+            CalcError::ParameterError => write!(f, "Parameter Error"),
+            CalcError::ExpressionError => write!(f, "Expression Error"),
+            CalcError::FunctionUndefined => write!(f, "Function Undefined"),
+            CalcError::OperatorUndefined => write!(f, "Operator Undefined"),
+            CalcError::EmptyExpression => write!(f, "Empty Expression"),
+            CalcError::InvalidNumber => write!(f, "Invalid Number"),
+            CalcError::UnitUndefined => write!(f, "Unit Undefined"),
+            CalcError::DimensionMismatch => write!(f, "Dimension Mismatch"),
+            CalcError::NoConvergence => write!(f, "No Convergence"),
+            CalcError::LimitExceeded => write!(f, "Limit Exceeded"),
+            CalcError::DomainError => write!(f, "Domain Error"),
+            CalcError::DepthExceeded => write!(f, "Depth Exceeded"),
+            CalcError::Cancelled => write!(f, "Cancelled"),
+            CalcError::DidYouMean(name) => write!(f, "Function Undefined, Did You Mean {}", name),
         }
     }
 }
 
+impl Value {
+    fn quantity(value: Float, unit: Unit) -> Self {
+        if unit.is_dimensionless() {
+            Value::Real(value)
+        } else { Value::Quantity(value, unit) }
+    }
+
+    fn rational(value: Rational) -> Self {
+        if value.is_integer() {
+            Value::Integer(value.into_numer_denom().0)
+        } else { Value::Rational(value) }
+    }
+
+    fn boolean(value: bool) -> Self {
+        Value::Integer(Integer::from(u8::from(value)))
+    }
+
+    fn truthy(self, context: &Context) -> Result<bool, CalcError> {
+        match self.fraction() {
+            Some(value) => Ok(value != 0),
+            None => Ok(self.real(context)? != 0.0),
+        }
+    }
+
+    fn compare(self, other: Self, context: &Context) -> Result<Option<std::cmp::Ordering>, CalcError> {
+        if let (Some(c2), Some(c1)) = (self.fraction(), other.fraction()) {
+            return Ok(c2.partial_cmp(&c1));
+        }
+        let (c2, u2) = self.split(context)?;
+        let (c1, u1) = other.split(context)?;
+        if !u2.same_dims(&u1) {
+            return Err(CalcError::DimensionMismatch);
+        }
+        Ok(c2.partial_cmp(&c1))
+    }
+
+    fn fraction(&self) -> Option<Rational> {
+        match self {
+            Value::Integer(value) => Some(Rational::from(value)),
+            Value::Rational(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn widen(self, context: &Context) -> Result<Self, CalcError> {
+        match self {
+            Value::Factors(_) => Value::Integer(self.integer()?).widen(context),
+            Value::Integer(value) => {
+                Ok(Value::Real(Float::with_val(context.prec, value).accuracy(context)?))
+            },
+            Value::Rational(value) => {
+                Ok(Value::Real(Float::with_val(context.prec, value).accuracy(context)?))
+            },
+            value => Ok(value),
+        }
+    }
+
+    fn integer(self) -> Result<Integer, CalcError> {
+        match self {
+            Value::Integer(value) => Ok(value),
+            Value::Factors(factors) => Ok(factors.iter().fold(Integer::from(1), |n, (p, exp)| {
+                n * Integer::from(p.pow(*exp))
+            })),
+            Value::Real(value) => value.to_integer().filter(|_| value.is_integer())
+                .ok_or(CalcError::ParameterError),
+            Value::Quantity(..) => Err(CalcError::DimensionMismatch),
+            _ => Err(CalcError::ParameterError),
+        }
+    }
+
+    fn split(self, context: &Context) -> Result<(Float, Unit), CalcError> {
+        match self.widen(context)? {
+            Value::Real(value) => Ok((value, Unit::none())),
+            Value::Quantity(value, unit) => Ok((value, unit)),
+            _ => Err(CalcError::ParameterError),
+        }
+    }
+
+    fn is_array(&self) -> bool {
+        matches!(self, Value::List(_) | Value::Matrix(_))
+    }
+
+    fn negate(self) -> Self {
+        match self {
+            Value::Real(value) => Value::Real(-value),
+            Value::Quantity(value, unit) => Value::Quantity(-value, unit),
+            Value::List(items) => Value::List(items.into_iter().map(|item| -item).collect()),
+            Value::Matrix(rows) => Value::Matrix(rows.into_iter().map(|row| {
+                row.into_iter().map(|item| -item).collect()
+            }).collect()),
+            Value::Complex(items) => Value::Complex(items.into_iter().map(|(re, im)| (-re, -im)).collect()),
+            Value::Integer(value) => Value::Integer(-value),
+            Value::Rational(value) => Value::Rational(-value),
+            Value::Factors(mut factors) => {
+                match factors.first_mut() {
+                    Some((p, _)) if *p == -1 => { factors.remove(0); },
+                    _ => factors.insert(0, (Integer::from(-1), 1)),
+                }
+                Value::Factors(factors)
+            },
+        }
+    }
+
+    fn real(self, context: &Context) -> Result<Float, CalcError> {
+        match self.widen(context)? {
+            Value::Real(value) => Ok(value),
+            Value::Quantity(..) => Err(CalcError::DimensionMismatch),
+            _ => Err(CalcError::ParameterError),
+        }
+    }
+
+    fn cells(&self, digits: Option<usize>) -> Result<Vec<Vec<String>>, CalcError> {
+        match self {
+            Value::Matrix(rows) => rows.iter().map(|row| {
+                row.iter().map(|item| item.to_round(digits)).collect()
+            }).collect(),
+            _ => Ok(vec![vec![self.to_round(digits)?]]),
+        }
+    }
+
+    pub fn to_round(&self, digits: Option<usize>) -> Result<String, CalcError> {
+        let mut out = String::new();
+        self.write_round(&mut out, digits)?;
+        Ok(out)
+    }
+
+    pub fn write_round<W: fmt::Write>(&self, out: &mut W, digits: Option<usize>) -> Result<(), CalcError> {
+        match self {
+            Value::Real(value) => value.write_round(out, digits),
+            Value::Integer(value) => Ok(write!(out, "{}", value)?),
+            Value::Rational(value) => write_ratio(out, value, digits),
+            Value::Factors(factors) if factors.is_empty() => Ok(out.write_char('1')?),
+            Value::Factors(factors) => {
+                for (index, (p, exp)) in factors.iter().enumerate() {
+                    if index > 0 {
+                        out.write_str(" × ")?;
+                    }
+                    if *exp > 1 {
+                        write!(out, "{}^{}", p, exp)?;
+                    } else { write!(out, "{}", p)?; }
+                }
+                Ok(())
+            },
+            Value::Quantity(value, unit) => {
+                unit.display(value).write_round(out, digits)?;
+                Ok(write!(out, " {}", unit.name())?)
+            },
+            Value::List(items) => write_list(out, items, digits),
+            Value::Matrix(rows) => {
+                out.write_char('[')?;
+                for (index, row) in rows.iter().enumerate() {
+                    if index > 0 {
+                        out.write_str(", ")?;
+                    }
+                    write_list(out, row, digits)?;
+                }
+                Ok(out.write_char(']')?)
+            },
+            Value::Complex(items) => {
+                out.write_char('[')?;
+                for (index, (re, im)) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.write_str(", ")?;
+                    }
+                    let (re, im) = (re.to_round(digits)?, im.to_round(digits)?);
+                    match (im.trim_start_matches('-'), im.starts_with('-')) {
+                        ("0", _) => out.write_str(&re)?,
+                        _ if re == "0" => write!(out, "{}i", im)?,
+                        (abs, true) => write!(out, "{} - {}i", re, abs)?,
+                        (abs, false) => write!(out, "{} + {}i", re, abs)?,
+                    }
+                }
+                Ok(out.write_char(']')?)
+            }
+        }
+    }
+
+    pub fn to_grid(&self, digits: Option<usize>) -> Result<String, CalcError> {
+        if !matches!(self, Value::Matrix(_)) {
+            return self.to_round(digits);
+        }
+        let cells = self.cells(digits)?;
+        let width = (0..cells[0].len()).map(|j| {
+            cells.iter().map(|row| row[j].len()).max().unwrap_or(0)
+        }).collect::<Vec<_>>();
+        let rows = cells.iter().map(|row| {
+            let row = row.iter().zip(&width).map(|(cell, &width)| format!("{:>width$}", cell))
+                .collect::<Vec<_>>();
+            format!("[ {} ]", row.join("  "))
+        }).collect::<Vec<_>>();
+        Ok(rows.join("\n"))
+    }
+}
+
 impl Calculator {
     pub fn new(prec: u32) -> Self {
-        let prec = max(64, prec);
+        Self::with(Context::new(max(64, prec)), Random::shared())
+    }
+
+    fn with(context: Context, random: Arc<Mutex<Random>>) -> Self {
         Self {
             state: State::Initial,
-            context: Context::new(prec),
+            context,
             numbers: Vec::with_capacity(32),
-            function: vec![None; 32],
+            variables: Vec::new(),
+            random,
+            mark: 0,
+            function: Vec::with_capacity(32),
             operator: Vec::with_capacity(32),
             marker: Marker::Init,
-            bracket: 0,
+            level: 0,
         }
     }
 
     pub fn reset(&mut self) {
         self.numbers.clear();
         self.state = State::Initial;
-        self.function.fill(None);
+        self.function.clear();
         self.marker = Marker::Init;
         self.operator.clear();
-        self.bracket = 0;
     }
 
-    fn finish(&mut self, expr: &[u8], locat: usize, end_idx: usize) -> Result<Float, CalcError> {
+    fn scope(&self, prec: u32) -> Calculator {
+        let max = max_value(prec, self.context.magnitude);
+        let context = Context { max, prec, ..self.context.clone() };
+        let mut scope = Calculator::with(context, Arc::clone(&self.random));
+        scope.level = self.level + self.function.len() + 1;
+        scope.variables = self.variables.clone();
+        scope
+    }
+
+    fn random(&self) -> MutexGuard<'_, Random> {
+        self.random.lock().unwrap_or_else(|poison| poison.into_inner())
+    }
+
+    pub fn set_log(&mut self, log: LogBase) {
+        self.context.log = log;
+    }
+
+    pub fn set_magnitude(&mut self, magnitude: Magnitude) {
+        self.context.magnitude = magnitude;
+        self.context.max = max_value(self.context.prec, magnitude);
+    }
+
+    pub fn set_arithmetic(&mut self, arithmetic: Arithmetic) {
+        self.context.arithmetic = arithmetic;
+    }
+
+    pub fn set_numbers(&mut self, numbers: Numbers) {
+        self.context.numbers = numbers;
+    }
+
+    pub fn set_percent(&mut self, percent: Percent) {
+        self.context.percent = percent;
+    }
+
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.context.depth = depth;
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.context.limits = limits;
+    }
+
+    /// Setting the token aborts the evaluation in progress; the next top-level `run` clears it.
+    pub fn cancel_token(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.context.cancel)
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.random().seed(seed);
+    }
+
+    pub fn is_volatile(&self) -> bool {
+        self.random().draws != self.mark
+    }
+
+    fn bind(&mut self, name: &[u8], value: Value) {
+        match self.variables.iter_mut().find(|(var, _)| var == name) {
+            Some((_, bound)) => *bound = value,
+            None => self.variables.push((name.to_vec(), value)),
+        }
+    }
+
+    fn value(&mut self, expr: &[u8]) -> Result<Value, CalcError> {
+        self.run(expr).inspect_err(|_| self.reset())
+    }
+
+    fn evaluate(&mut self, expr: &[u8]) -> Result<Float, CalcError> {
+        self.value(expr)?.real(&self.context)
+    }
+
+    fn list(&self, items: &[&[u8]]) -> Result<Value, CalcError> {
+        if let [item] = items {
+            if item.trim_ascii().is_empty() {
+                return Ok(Value::List(Vec::new()));
+            }
+        }
+        let mut scope = self.scope(self.context.prec);
+        let items = items.iter().map(|item| scope.value(item)).collect::<Result<Vec<_>, _>>()?;
+        if items.iter().all(|item| matches!(item, Value::List(_))) {
+            let rows = items.into_iter().filter_map(|item| match item {
+                Value::List(row) => Some(row),
+                _ => None,
+            }).collect::<Vec<_>>();
+            if rows[0].is_empty() || rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err(CalcError::ParameterError);
+            }
+            return Ok(Value::Matrix(rows));
+        }
+        items.into_iter().map(|item| item.real(&self.context)).collect::<Result<Vec<_>, _>>().map(Value::List)
+    }
+
+    fn operand(&mut self, value: Value) -> Result<(), CalcError> {
+        if matches!(self.state, State::Operator | State::Initial)
+            && !matches!(self.marker, Marker::Number | Marker::Func) {
+            let value = if matches!(self.marker, Marker::NegSub) {
+                value.negate()
+            } else { value };
+            self.numbers.push(value);
+            self.state = State::Operand;
+            self.marker = Marker::Const;
+            return Ok(());
+        }
+        Err(CalcError::ExpressionError)
+    }
+
+    fn finish(&mut self) -> Result<Value, CalcError> {
         if matches!(self.marker, Marker::Init) {
             return Err(CalcError::EmptyExpression);
-        } else if self.bracket > 0 || matches!(self.marker, Marker::NegSub | Marker::Char | Marker::Func) {
+        } else if !self.function.is_empty() || matches!(self.marker, Marker::NegSub | Marker::Char | Marker::Func) {
             return Err(CalcError::ExpressionError);
         }
-        if matches!(self.state, State::Operator | State::Initial) {
-            self.numbers.push(extract(expr, &self.context, locat, end_idx)?);
-            self.state = State::Operand;
-        }
         while let Some(op) = self.operator.pop() {
             let value = op.computing(self)?;
             self.numbers.push(value);
         }
-        let result = self.numbers.pop().unwrap();
+        let result = self.numbers.pop().ok_or(CalcError::ExpressionError)?;
         self.reset(); Ok(result)
     }
 
-    pub fn run<S: AsRef<[u8]>>(&mut self, expr: S) -> Result<Float, CalcError> {
+    fn operate(&mut self, ch: u8) -> Result<(), CalcError> {
+        while let Some(&op) = self.operator.last().filter(|&&op| op != b'(') {
+            if op.priority()? >= ch.priority()? {
+                self.operator.pop();
+                let value = op.computing(self)?;
+                self.numbers.push(value);
+            } else {
+                break;
+            }
+        }
+        self.operator.push(ch);
+        self.state = State::Operator;
+        self.marker = Marker::Char;
+        Ok(())
+    }
+
+    fn percent(&mut self) -> Result<(), CalcError> {
+        self.numbers.push(Value::Integer(Integer::from(100)));
+        let value = b'/'.computing(self)?;
+        let base = match self.operator.last() {
+            Some(b'+' | b'-') => self.numbers.last().cloned(),
+            _ => None,
+        };
+        self.numbers.push(value);
+        if let Some(base) = base {
+            self.numbers.push(base);
+            let value = b'*'.computing(self)?;
+            self.numbers.push(value);
+        }
+        self.marker = Marker::Const;
+        Ok(())
+    }
+
+    fn convert(&mut self, expr: &[u8], start: usize) -> Result<usize, CalcError> {
+        if !matches!(self.marker, Marker::Number | Marker::RParen | Marker::Const) {
+            return Err(CalcError::ExpressionError);
+        }
+        while let Some(op) = self.operator.pop_if(|op| *op != b'(') {
+            let value = op.computing(self)?;
+            self.numbers.push(value);
+        }
+        let (unit, end) = units::parse_target(expr, start, self.context.prec)?;
+        let (value, from) = self.numbers.pop().ok_or(CalcError::ExpressionError)?.split(&self.context)?;
+        if !from.same_dims(&unit) {
+            return Err(CalcError::DimensionMismatch);
+        }
+        self.numbers.push(Value::Quantity(value, unit));
+        self.marker = Marker::Const;
+        Ok(end)
+    }
+
+    fn word(&mut self, expr: &[u8], start: usize, end: usize) -> Result<usize, CalcError> {
+        let name = &expr[start..end];
+        let prec = self.context.prec;
+        let constant = match name {
+            b"to" | b"in" => return self.convert(expr, end),
+            b"and" | b"or" => {
+                if !matches!(self.marker, Marker::Number | Marker::RParen | Marker::Const) {
+                    return Err(CalcError::ExpressionError);
+                }
+                self.operate(if name == b"and" { b'&' } else { b'|' })?;
+                return Ok(end);
+            },
+            b"P" => Some(&Constant::Pi),
+            b"Y" => Some(&Constant::Euler),
+            b"C" => Some(&Constant::Catalan),
+            b"L" => Some(&Constant::Log2),
+            b"inf" | b"nan" if !self.context.strict() => {
+                let special = if name == b"inf" { Special::Infinity } else { Special::Nan };
+                self.operand(Value::Real(Float::with_val(prec, special)))?;
+                return Ok(end);
+            },
+            _ => None,
+        };
+        if let Some(constant) = constant {
+            self.operand(Value::Real(Float::with_val(prec, constant)))?;
+            return Ok(end);
+        } else if let Some((_, value)) = self.variables.iter().find(|(var, _)| var == name) {
+            self.operand(value.clone())?;
+            return Ok(end);
+        }
+        let glued = start > 0 && matches!(expr[start - 1], b'0'..=b'9' | b'.');
+        let after = matches!(self.marker, Marker::Number | Marker::RParen | Marker::Const);
+        let (scale, offset, unit) = units::resolve(name, prec).ok_or_else(|| if glued {
+            CalcError::ExpressionError
+        } else if after { CalcError::UnitUndefined } else { suggest(name) })?;
+        if after {
+            if let Some(offset) = offset {
+                let value = self.numbers.pop().ok_or(CalcError::ExpressionError)?.real(&self.context)?;
+                let value = Float::with_val(prec, &value + &offset) * &scale;
+                self.numbers.push(Value::Quantity(value.accuracy(&self.context)?, unit));
+                self.marker = Marker::Const;
+                return Ok(end);
+            }
+            self.operate(b'_')?;
+        } else if matches!(self.marker, Marker::Func) {
+            return Err(CalcError::ExpressionError);
+        }
+        let value = if matches!(self.marker, Marker::NegSub) {
+            0.0 - scale
+        } else { scale };
+        self.numbers.push(Value::Quantity(value, unit));
+        self.state = State::Operand;
+        self.marker = Marker::Const;
+        Ok(end)
+    }
+
+    pub fn run<S: AsRef<[u8]>>(&mut self, expr: S) -> Result<Value, CalcError> {
         let bytes = expr.as_ref();
+        if self.level >= self.context.depth {
+            return Err(CalcError::DepthExceeded);
+        } else if self.level == 0 {
+            self.context.cancel.store(false, Ordering::Relaxed);
+            self.context.steps.store(0, Ordering::Relaxed);
+            self.context.deadline = self.context.limits.timeout.map(|timeout| Instant::now() + timeout);
+        } else if self.context.steps.fetch_add(1, Ordering::Relaxed) >= self.context.limits.evaluations {
+            return Err(CalcError::LimitExceeded);
+        }
+        let draws = self.random().draws;
+        self.mark = draws;
         let mut locat: usize = 0;
-        for (index, &valid) in bytes.iter().enumerate() {
+        let mut index: usize = 0;
+        while let Some(&valid) = bytes.get(index) {
+            self.context.check()?;
             match valid {
                 b'0'..=b'9' | b'.' => {
-                    if !matches!(self.marker, Marker::RParen | Marker::Const | Marker::Func) {
+                    if !matches!(self.marker, Marker::Number | Marker::RParen | Marker::Const | Marker::Func) {
+                        let end = number_end(bytes, index);
+                        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+                            return Err(CalcError::InvalidNumber);
+                        }
+                        let value = if self.context.decimal() {
+                            decimal(&bytes[index..end], &self.context)?
+                        } else { Value::Real(extract(bytes, &self.context, index, end)?) };
+                        let value = match value {
+                            Value::Real(value) if matches!(self.marker, Marker::NegSub) => Value::Real(0.0 - value),
+                            value if matches!(self.marker, Marker::NegSub) => value.negate(),
+                            value => value,
+                        };
+                        self.numbers.push(value);
+                        self.state = State::Operand;
                         self.marker = Marker::Number;
+                        index = end;
                         continue;
                     }
                     return Err(CalcError::ExpressionError);
                 },
-                ch @ b'a'..=b'z' | ch @ b'E' => {
-                    if (ch == b'e' || ch == b'E') && matches!(self.marker, Marker::Number) {
+                b'a'..=b'z' | b'A'..=b'Z' => {
+                    let end = word_end(bytes, index);
+                    if bytes.get(end) != Some(&b'(') || matches!(&bytes[index..end], b"and" | b"or") {
+                        index = self.word(bytes, index, end)?;
                         continue;
                     } else if !matches!(self.marker, Marker::RParen | Marker::Const | Marker::NegSub | Marker::Number) {
+                        if let Some(special) = SPECIAL.get(canonical(&bytes[index..end])) {
+                            let (args, close) = arguments(bytes, end)?;
+                            let value = special(self, &args)?;
+                            self.numbers.push(value);
+                            self.state = State::Operand;
+                            self.marker = Marker::RParen;
+                            index = close;
+                            continue;
+                        }
                         self.marker = Marker::Func;
+                        locat = index;
+                        index = end;
                         continue;
                     }
                     return Err(CalcError::ExpressionError);
                 },
-                ch @ b'+' | ch @ b'-' | ch @ b'*' | ch @ b'/' | ch @ b'%' | ch @ b'^' => {
-                    if (ch == b'-' || ch == b'+') && matches!(self.marker, Marker::Number) {
-                        let prev_byte = bytes.get(index-1);
-                        if matches!(prev_byte, Some(b'e' | b'E')) {
-                            continue;
-                        }
+                ch @ b'<' | ch @ b'>' | ch @ b'!' | ch @ b'=' if ch != b'=' || bytes.get(index + 1) == Some(&b'=') => {
+                    if !matches!(self.marker, Marker::Number | Marker::RParen | Marker::Const) {
+                        return Err(CalcError::ExpressionError);
                     }
+                    let (op, width) = match (ch, bytes.get(index + 1)) {
+                        (b'<', Some(b'=')) => (b'{', 2),
+                        (b'>', Some(b'=')) => (b'}', 2),
+                        (b'=' | b'!', Some(b'=')) => (ch, 2),
+                        (b'<' | b'>', _) => (ch, 1),
+                        _ => return Err(CalcError::OperatorUndefined),
+                    };
+                    self.operate(op)?;
+                    index += width;
+                    continue;
+                },
+                ch @ b'+' | ch @ b'-' | ch @ b'*' | ch @ b'/' | ch @ b'%' | ch @ b'^' => {
                     if ch == b'-' && matches!(self.marker, Marker::Init | Marker::LParen | Marker::Char) {
                         self.marker = Marker::NegSub;
+                        index += 1;
                         continue;
                     } else if !matches!(self.marker, Marker::Number | Marker::RParen | Marker::Const) {
                         return Err(CalcError::ExpressionError);
+                    } else if ch == b'%' && self.context.percent == Percent::Percentage {
+                        self.percent()?;
+                        index += 1;
+                        continue;
+                    } else if ch == b'/' && bytes.get(index + 1) == Some(&b'/') {
+                        self.operate(b'\\')?;
+                        index += 2;
+                        continue;
                     }
-                    if matches!(self.state, State::Operator | State::Initial) {
-                        self.numbers.push(extract(bytes, &self.context, locat, index)?);
-                        self.state = State::Operand;
-                    }
-                    while self.operator.len() != 0 && self.operator.last() != Some(&b'(') {
-                        if self.operator.last().unwrap().priority()? >= ch.priority()? {
-                            let value = self.operator.pop().unwrap().computing(self)?;
-                            self.numbers.push(value);
-                        } else {
-                            break;
-                        }
-                    }
-                    self.operator.push(ch);
-                    self.state = State::Operator;
-                    self.marker = Marker::Char;
-                    locat = index + 1;
+                    self.operate(ch)?;
+                    index += 1;
                     continue;
                 },
                 ch @ b'(' => {
-                    if matches!(self.marker, Marker::Func) {
+                    let func = if matches!(self.marker, Marker::Func) {
                         let name = &bytes[locat..index];
-                        if let Some(&func_ptr) = MATH.get(name) {
-                            self.function[self.bracket+1] = Some(func_ptr);
-                        } else {
-                            return Err(CalcError::FunctionUndefined);
-                        }
-                    }
-                    if matches!(self.state, State::Operator | State::Initial) {
-                        if !matches!(self.marker, Marker::Number | Marker::NegSub) {
-                            self.operator.push(ch);
-                            locat = index + 1;
-                            self.marker = Marker::LParen;
-                            self.bracket += 1;
-                            continue;
+                        Some(*MATH.get(canonical(name)).ok_or_else(|| suggest(name))?)
+                    } else { None };
+                    if matches!(self.state, State::Operator | State::Initial)
+                        && !matches!(self.marker, Marker::Number | Marker::NegSub) {
+                        if self.level + self.function.len() >= self.context.depth {
+                            return Err(CalcError::DepthExceeded);
                         }
+                        self.function.push(func);
+                        self.operator.push(ch);
+                        self.marker = Marker::LParen;
+                        index += 1;
+                        continue;
                     }
                     return Err(CalcError::ExpressionError);
                 },
                 b')' => {
-                    if matches!(self.state, State::Operator | State::Initial) {
-                        if matches!(self.marker, Marker::Number) {
-                            self.numbers.push(extract(bytes, &self.context, locat, index)?);
-                            self.state = State::Operand;
+                    if matches!(self.state, State::Operand) && !self.function.is_empty() {
+                        while let Some(op) = self.operator.pop_if(|op| *op != b'(') {
+                            let value = op.computing(self)?;
+                            self.numbers.push(value);
                         }
-                    }
-                    if matches!(self.state, State::Operand) {
-                        if self.bracket > 0 {
-                            while self.operator.last() != Some(&b'(') {
-                                let value = self.operator.pop().unwrap().computing(self)?;
-                                self.numbers.push(value);
-                            }
-                            if let Some(func) = self.function[self.bracket].take() {
-                                let value = self.numbers.pop().unwrap();
-                                self.numbers.push(func(value, &self.context)?);
-                            }
-                            locat = index + 1;
-                            self.operator.pop();
-                            self.marker = Marker::RParen;
-                            self.bracket -= 1;
-                            continue;
+                        if let Some(func) = self.function.pop().flatten() {
+                            let value = self.numbers.pop().ok_or(CalcError::ExpressionError)?.real(&self.context)?;
+                            self.numbers.push(Value::Real(func(value, &self.context)?));
                         }
+                        self.operator.pop();
+                        self.marker = Marker::RParen;
+                        index += 1;
+                        continue;
                     }
                     return Err(CalcError::ExpressionError);
                 },
-                b'=' | b'\n' | b'\r' => {
-                    return self.finish(bytes, locat, index);
+                b'[' => {
+                    let (items, close) = arguments(bytes, index)?;
+                    let value = self.list(&items)?;
+                    self.operand(value)?;
+                    index = close;
+                    continue;
                 },
-                ch @ b'P' | ch @ b'Y' | ch @ b'C' | ch @ b'L' => {
-                    if matches!(self.state, State::Operator | State::Initial) {
-                        let constant = match ch {
-                            b'P' => &Constant::Pi,
-                            b'Y' => &Constant::Euler,
-                            b'C' => &Constant::Catalan,
-                            b'L' => &Constant::Log2,
-                            _ => return Err(CalcError::UnknownError)
-                        };
-                        if !matches!(self.marker, Marker::Number | Marker::Func) {
-                            let value = if matches!(self.marker, Marker::NegSub) {
-                                0.0 - Float::with_val(self.context.prec, constant)
-                            } else {
-                                Float::with_val(self.context.prec, constant)
-                            };
-                            self.numbers.push(value);
-                            self.state = State::Operand;
-                            self.marker = Marker::Const;
-                            locat = index + 1;
-                            continue;
-                        }
-                    }
-                    return Err(CalcError::ExpressionError);
+                b' ' | b'\t' => {
+                    index += 1;
+                    continue;
+                },
+                b'=' | b'\n' | b'\r' => {
+                    return self.finish();
                 },
                 _ => return Err(CalcError::OperatorUndefined),
             }
         }
-        self.finish(bytes, locat, bytes.len())
+        self.finish()
+    }
+
+    pub fn run_script<S: AsRef<[u8]>>(&mut self, script: S) -> Vec<Statement> {
+        let mut results = Vec::new();
+        for (index, line) in script.as_ref().split(|&b| b == b'\n').enumerate() {
+            let line = line.split(|&b| b == b'#').next().unwrap_or_default();
+            for statement in line.split(|&b| b == b';').filter(|s| !s.trim_ascii().is_empty()) {
+                let (name, result) = self.statement(statement);
+                results.push(Statement { line: index + 1, name, result });
+                if self.context.cancel.load(Ordering::Relaxed) {
+                    return results;
+                }
+            }
+        }
+        results
+    }
+
+    fn statement(&mut self, statement: &[u8]) -> (Option<String>, Result<Value, CalcError>) {
+        let split = statement.iter().enumerate()
+            .position(|(index, &b)| b == b'=' && terminal(statement, index));
+        let Some(split) = split.filter(|&split| !statement[split + 1..].trim_ascii().is_empty()) else {
+            return (None, self.value(statement));
+        };
+        let name = match identifier(&statement[..split]) {
+            Ok(b"to" | b"in" | b"and" | b"or" | b"P" | b"Y" | b"C" | b"L") | Err(_) => {
+                return (None, Err(CalcError::ExpressionError));
+            },
+            Ok(name) => name,
+        };
+        let result = self.value(&statement[split + 1..]);
+        if let Ok(value) = &result {
+            self.bind(name, value.clone());
+        }
+        (Some(String::from_utf8_lossy(name).into_owned()), result)
     }
 
     pub fn run_round<S: AsRef<[u8]>>(
@@ -570,4 +1591,4 @@ impl Calculator {
             Err(err) => Err(err)
         }
     }
-}
\ No newline at end of file
+}
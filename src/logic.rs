@@ -0,0 +1,52 @@
+use rug::Float;
+use crate::{Calculator, CalcError, Value};
+
+pub fn condition(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [test, then, other] = args else {
+        return Err(CalcError::ParameterError);
+    };
+    let mut scope = calc.scope(calc.context.prec);
+    if scope.value(test)?.truthy(&calc.context)? {
+        scope.value(then)
+    } else { scope.value(other) }
+}
+
+pub fn clamp(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [x, lo, hi] = args else {
+        return Err(CalcError::ParameterError);
+    };
+    let context = &calc.context;
+    let mut scope = calc.scope(context.prec);
+    let (x, lo, hi) = (scope.value(x)?, scope.value(lo)?, scope.value(hi)?);
+    if lo.clone().compare(hi.clone(), context)?.is_none_or(|o| o.is_gt()) {
+        return Err(CalcError::ParameterError);
+    }
+    if x.clone().compare(lo.clone(), context)?.is_some_and(|o| o.is_lt()) {
+        Ok(lo)
+    } else if x.clone().compare(hi.clone(), context)?.is_some_and(|o| o.is_gt()) {
+        Ok(hi)
+    } else { Ok(x) }
+}
+
+pub fn approx(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (a, b, eps) = match args {
+        [a, b] => (a, b, None),
+        [a, b, eps] => (a, b, Some(eps)),
+        _ => return Err(CalcError::ParameterError),
+    };
+    let context = &calc.context;
+    let mut scope = calc.scope(context.prec);
+    let (a, b) = (scope.value(a)?.real(context)?, scope.value(b)?.real(context)?);
+    let diff = Float::with_val(context.prec, &a - &b).abs();
+    let tolerance = match eps {
+        Some(eps) => scope.value(eps)?.real(context)?,
+        None => {
+            let scale = a.abs().max(&b.abs()).max(&Float::with_val(context.prec, 1));
+            scale * Float::with_val(context.prec, Float::i_exp(1, 8 - context.prec as i32))
+        },
+    };
+    if tolerance < 0.0 {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(Value::boolean(diff <= tolerance))
+}
@@ -1,4 +1,5 @@
-use calc::Calculator;
+use calc::{Calculator, Limits};
+use std::time::Duration;
 use once_cell::sync::Lazy;
 use iced::window::Position;
 use textwrap::fill;
@@ -47,6 +48,7 @@ struct GCalculator {
     value: String,
     calc: Calculator,
     history: Vec<CalcResult>,
+    grid: Option<String>,
     scroll: RelativeOffset,
     state: State,
 }
@@ -66,7 +68,6 @@ enum Message {
 fn trunc(lens: &str) -> String {
     let valid = lens
         .chars()
-        .into_iter()
         .map(|x| x.to_string())
         .collect::<Vec<_>>();
 
@@ -192,12 +193,15 @@ impl CalcResult {
 
 impl Default for GCalculator {
     fn default() -> Self {
+        let mut calc = Calculator::new(2560);
+        calc.set_limits(Limits { timeout: Some(Duration::from_secs(10)), ..Limits::default() });
         GCalculator {
             show: String::from("0"),
             value: String::from("0"),
-            calc: Calculator::new(2560),
+            calc,
             scroll: RelativeOffset::START,
             history: Vec::new(),
+            grid: None,
             state: State::None,
         }
     }
@@ -217,7 +221,9 @@ impl GCalculator {
             Message::Operator(op, lb) => {
                 let expr = self.value.clone();
                 if self.oper_event(&op, lb) {
-                    let valid = if self.show[0..1]
+                    let valid = if let Some(grid) = self.grid.take() {
+                            grid
+                        } else if self.show[0..1]
                         .parse::<f64>().is_ok() {
                             self.value.clone()
                         } else {
@@ -230,7 +236,7 @@ impl GCalculator {
                     if self.history.len() > 30 {
                         self.history.remove(0);
                     }
-                    if self.history.len() >= 1 {
+                    if !self.history.is_empty() {
                         self.scroll = RelativeOffset::END;
                         return operation::snap_to(
                             SCROLL.clone(),
@@ -244,8 +250,7 @@ impl GCalculator {
     }
 
     fn view(&self) -> Element<'_, Message> {
-        let custom_rule: for<'a> fn(&'a _) -> _;
-        custom_rule = |_: &Theme| -> rule::Style {
+        let custom_rule: for<'a> fn(&'a _) -> _ = |_: &Theme| -> rule::Style {
             rule::Style {
                 snap: true,
                 radius: 0.0.into(),
@@ -254,8 +259,7 @@ impl GCalculator {
             }
         };
 
-        let custom_main: for<'a> fn(&'a _) -> _;
-        custom_main = |_: &Theme| -> Style {
+        let custom_main: for<'a> fn(&'a _) -> _ = |_: &Theme| -> Style {
             let color = Color::from([0.2, 0.2, 0.2]);
             Style {
                 background: Some(Background::Color(color)),
@@ -279,7 +283,7 @@ impl GCalculator {
                     .height(Length::Shrink)
                     .font(CONSOLA_NORMAL)
                     .line_height(LineHeight::Absolute(Pixels(23.0))),
-                text(format!("{}", fill(&d.result(), 60)))
+                text(fill(&d.result(), 60).to_string())
                     .size(21.0)
                     .width(Length::Fill)
                     .height(Length::Shrink)
@@ -291,7 +295,7 @@ impl GCalculator {
             ].into()
         };
 
-        let history_list = if self.history.len() != 0 {
+        let history_list = if !self.history.is_empty() {
             self.history.iter().enumerate().fold(
                 column![],
                 |column, (index, event)| {
@@ -307,7 +311,7 @@ impl GCalculator {
                     .style(|_theme| text::Style {
                         color: Some(Color::from([0.35, 0.35, 0.35])),
                     })
-            ].into()
+            ]
         };
 
         let result_main = container(
@@ -393,7 +397,7 @@ impl GCalculator {
 
         let operator = |op: char, sz: f32, pd: f32|
             -> Element<Message> {
-            oper_label(op.clone(), op, sz, pd)
+            oper_label(op, op, sz, pd)
         };
 
         let func_label = |fun: &'static str, lb: &'static str|
@@ -423,7 +427,7 @@ impl GCalculator {
                 row![
                     func_label("Cot", "cot("), func_label("Coth", "coth("),
                     func_label("Ai", "ai("), func_label("Cbrt", "cbrt("),
-                    func_label("Li2", "li("), func_label("Erfc", "erfc("),
+                    func_label("Li2", "li2("), func_label("Erfc", "erfc("),
                     func_label("Sec", "sec("), func_label("Csc", "csc("),
                     func_label("Csch", "csch("),func_label("Eint", "eint("),
                     func_label("Trunc", "trunc("),
@@ -454,15 +458,15 @@ impl GCalculator {
                     digit('1'), digit('2'), digit('3'),
                     oper_label('−', '-', 26.0, 3.0), operator('π', 24.0, 3.0),
                     oper_label('\u{039B}', '^', 21.0, 3.0), func_label("Sqrt", "sqrt("),
-                    func_label("Log2", "log("), func_label("Log10", "logx("),
-                    func_label("Asin", "asin("), func_label("Exp10", "expx("),
+                    func_label("Log2", "log2("), func_label("Log10", "log10("),
+                    func_label("Asin", "asin("), func_label("Exp10", "exp10("),
                 ].height(45.0).spacing(3.0),
                 row![
                     operator('%', 24.0, 5.0), digit('0'), operator('.', 24.0, 0.0),
                     operator('+', 26.0, 3.0), operator('γ', 23.0, 0.0),
                     operator('=', 25.0, 3.0), func_label("Fac", "fac("),
                     func_label("Abs", "abs("), func_label("Ln", "ln("),
-                    func_label("Exp", "exp("), func_label("Exp2", "expt("),
+                    func_label("Exp", "exp("), func_label("Exp2", "exp2("),
                 ].height(45.0).spacing(3.0),
             ].padding(3.0).spacing(3.0)
         ].into()
@@ -506,8 +510,11 @@ impl GCalculator {
                 self.state = State::Set;
                 if self.value != "0" {
                     let expr = oper_repl(self.value.as_str());
-                    match self.calc.run_round(expr, Some(6)) {
-                        Ok(valid) => {
+                    match self.calc.run(expr).and_then(|value| {
+                        Ok((value.to_round(Some(6))?, value.to_grid(Some(6))?))
+                    }) {
+                        Ok((valid, grid)) => {
+                            self.grid = Some(grid);
                             self.value = valid.clone();
                             self.show = trunc(valid.as_str())
                         },
@@ -0,0 +1,266 @@
+use rug::Float;
+use std::cmp::Ordering;
+use crate::{ByteExt, Calculator, CalcError, Context, FloatExt, Value};
+
+type Rows = Vec<Vec<Float>>;
+
+struct Echelon {
+    rows: Rows,
+    pivots: Vec<usize>,
+    det: Float,
+}
+
+fn reduce(rows: &[Vec<Float>], cols: usize, prec: u32) -> Echelon {
+    let work = prec + 64;
+    let mut rows: Rows = rows.iter().map(|row| {
+        row.iter().map(|x| Float::with_val(work, x)).collect()
+    }).collect();
+    let scale = rows.iter().flat_map(|row| &row[..cols])
+        .fold(Float::with_val(work, 0), |max, x| max.max(&Float::with_val(work, x.abs_ref())));
+    let tol = scale * Float::with_val(work, Float::i_exp(1, -(prec as i32)));
+    let mut det = Float::with_val(work, 1);
+    let mut pivots = Vec::new();
+    for col in 0..cols {
+        let top = pivots.len();
+        if top == rows.len() {
+            break;
+        }
+        let best = (top..rows.len()).max_by(|&a, &b| {
+            rows[a][col].cmp_abs(&rows[b][col]).unwrap_or(Ordering::Equal)
+        }).unwrap_or(top);
+        if !rows[best][col].is_nan() && rows[best][col].cmp_abs(&tol) != Some(Ordering::Greater) {
+            continue;
+        }
+        if best != top {
+            rows.swap(best, top);
+            det = -det;
+        }
+        let pivot = rows[top][col].clone();
+        det *= &pivot;
+        rows[top].iter_mut().for_each(|x| *x /= &pivot);
+        let lead = rows[top].clone();
+        for (index, row) in rows.iter_mut().enumerate() {
+            let factor = row[col].clone();
+            if index == top || factor == 0 {
+                continue;
+            }
+            for (x, y) in row.iter_mut().zip(&lead) {
+                *x -= Float::with_val(work, &factor * y);
+            }
+        }
+        pivots.push(col);
+    }
+    if pivots.len() < cols {
+        det = Float::with_val(work, 0);
+    }
+    Echelon { rows, pivots, det }
+}
+
+fn round(calc: &Calculator, x: &Float) -> Result<Float, CalcError> {
+    Float::with_val(calc.context.prec, x).accuracy(&calc.context)
+}
+
+pub fn values<const N: usize>(calc: &Calculator, args: &[&[u8]]) -> Result<[Value; N], CalcError> {
+    let mut scope = calc.scope(calc.context.prec);
+    let values = args.iter().map(|arg| scope.value(arg)?.widen(&calc.context))
+        .collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|_| CalcError::ParameterError)
+}
+
+fn matrix(value: Value) -> Result<Rows, CalcError> {
+    match value {
+        Value::Matrix(rows) => Ok(rows),
+        Value::Quantity(..) => Err(CalcError::DimensionMismatch),
+        _ => Err(CalcError::ParameterError),
+    }
+}
+
+pub fn vector(value: Value) -> Result<Vec<Float>, CalcError> {
+    match value {
+        Value::List(items) => Ok(items),
+        Value::Matrix(rows) if rows.len() == 1 => Ok(rows.concat()),
+        Value::Matrix(rows) if rows[0].len() == 1 => Ok(rows.concat()),
+        Value::Quantity(..) => Err(CalcError::DimensionMismatch),
+        _ => Err(CalcError::ParameterError),
+    }
+}
+
+fn square(value: Value) -> Result<Rows, CalcError> {
+    let rows = matrix(value)?;
+    if rows.len() != rows[0].len() {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(rows)
+}
+
+fn product(row: &[Float], col: impl Iterator<Item = Float>, context: &Context) -> Result<Float, CalcError> {
+    let terms: Vec<Float> = row.iter().zip(col).map(|(x, y)| {
+        Float::with_val(context.prec + 32, x * y)
+    }).collect();
+    Float::with_val(context.prec, Float::sum(terms.iter())).accuracy(context)
+}
+
+fn each(items: &[Float], f: impl Fn(&Float) -> Result<Float, CalcError>) -> Result<Vec<Float>, CalcError> {
+    items.iter().map(f).collect()
+}
+
+fn scalar(op: u8, value: Value, n: &Float, left: bool, context: &Context) -> Result<Value, CalcError> {
+    let apply = |x: &Float| if left {
+        op.arithmetic(n, x, context)
+    } else { op.arithmetic(x, n, context) };
+    match value {
+        Value::List(items) => Ok(Value::List(each(&items, apply)?)),
+        Value::Matrix(rows) => rows.iter().map(|row| each(row, apply))
+            .collect::<Result<Rows, _>>().map(Value::Matrix),
+        _ => Err(CalcError::ParameterError),
+    }
+}
+
+fn zip(op: u8, c2: &[Float], c1: &[Float], context: &Context) -> Result<Vec<Float>, CalcError> {
+    if c2.len() != c1.len() {
+        return Err(CalcError::ParameterError);
+    }
+    c2.iter().zip(c1).map(|(x, y)| op.arithmetic(x, y, context)).collect()
+}
+
+pub fn operate(op: u8, c2: Value, c1: Value, context: &Context) -> Result<Value, CalcError> {
+    match (op, c2, c1) {
+        (_, Value::Quantity(..), _) | (_, _, Value::Quantity(..)) => Err(CalcError::DimensionMismatch),
+        (b'+' | b'-' | b'*', Value::Real(n), value) => scalar(op, value, &n, true, context),
+        (b'+' | b'-' | b'*' | b'/', value, Value::Real(n)) => scalar(op, value, &n, false, context),
+        (b'+' | b'-', Value::List(c2), Value::List(c1)) => Ok(Value::List(zip(op, &c2, &c1, context)?)),
+        (b'+' | b'-', Value::Matrix(c2), Value::Matrix(c1)) => {
+            if c2.len() != c1.len() {
+                return Err(CalcError::ParameterError);
+            }
+            c2.iter().zip(&c1).map(|(x, y)| zip(op, x, y, context))
+                .collect::<Result<Rows, _>>().map(Value::Matrix)
+        },
+        (b'*', Value::Matrix(c2), Value::Matrix(c1)) => {
+            if c2[0].len() != c1.len() {
+                return Err(CalcError::ParameterError);
+            }
+            c2.iter().map(|row| (0..c1[0].len()).map(|j| {
+                product(row, c1.iter().map(|r| r[j].clone()), context)
+            }).collect::<Result<Vec<_>, _>>()).collect::<Result<Rows, _>>().map(Value::Matrix)
+        },
+        (b'*', Value::Matrix(c2), Value::List(c1)) => {
+            if c2[0].len() != c1.len() {
+                return Err(CalcError::ParameterError);
+            }
+            c2.iter().map(|row| product(row, c1.iter().cloned(), context))
+                .collect::<Result<Vec<_>, _>>().map(Value::List)
+        },
+        (b'*', Value::List(c2), Value::Matrix(c1)) => {
+            if c2.len() != c1.len() {
+                return Err(CalcError::ParameterError);
+            }
+            (0..c1[0].len()).map(|j| product(&c2, c1.iter().map(|r| r[j].clone()), context))
+                .collect::<Result<Vec<_>, _>>().map(Value::List)
+        },
+        _ => Err(CalcError::ParameterError),
+    }
+}
+
+pub fn det(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a] = values(calc, args)?;
+    let rows = square(a)?;
+    let echelon = reduce(&rows, rows.len(), calc.context.prec);
+    Ok(Value::Real(round(calc, &echelon.det)?))
+}
+
+pub fn inv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a] = values(calc, args)?;
+    let rows = square(a)?;
+    let n = rows.len();
+    let identity = (0..n).map(|i| (0..n).map(|j| {
+        Float::with_val(calc.context.prec, u32::from(i == j))
+    }).collect()).collect();
+    system(calc, rows, identity).map(Value::Matrix)
+}
+
+pub fn system(calc: &Calculator, a: Rows, b: Rows) -> Result<Rows, CalcError> {
+    let n = a.len();
+    if b.len() != n {
+        return Err(CalcError::ParameterError);
+    }
+    let augmented: Rows = a.into_iter().zip(b).map(|(mut row, extra)| {
+        row.extend(extra);
+        row
+    }).collect();
+    let echelon = reduce(&augmented, n, calc.context.prec);
+    if echelon.pivots.len() < n {
+        return Err(CalcError::DivideByZero);
+    }
+    echelon.rows.iter().map(|row| {
+        row[n..].iter().map(|x| round(calc, x)).collect()
+    }).collect()
+}
+
+pub fn solve(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, b] = values(calc, args)?;
+    let a = square(a)?;
+    match b {
+        Value::Matrix(b) => system(calc, a, b).map(Value::Matrix),
+        b => {
+            let b = vector(b)?.into_iter().map(|x| vec![x]).collect();
+            Ok(Value::List(system(calc, a, b)?.concat()))
+        }
+    }
+}
+
+pub fn transpose(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a] = values(calc, args)?;
+    let rows = match a {
+        Value::List(items) if !items.is_empty() => return Ok(Value::Matrix(
+            items.into_iter().map(|x| vec![x]).collect()
+        )),
+        a => matrix(a)?,
+    };
+    Ok(Value::Matrix((0..rows[0].len()).map(|j| {
+        rows.iter().map(|row| row[j].clone()).collect()
+    }).collect()))
+}
+
+pub fn dot(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [u, v] = values(calc, args)?;
+    let (u, v) = (vector(u)?, vector(v)?);
+    if u.len() != v.len() {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(Value::Real(product(&u, v.into_iter(), &calc.context)?))
+}
+
+pub fn cross(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [u, v] = values(calc, args)?;
+    let (u, v) = (vector(u)?, vector(v)?);
+    if u.len() != 3 || v.len() != 3 {
+        return Err(CalcError::ParameterError);
+    }
+    (0..3).map(|i| {
+        let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+        let lhs = [u[j].clone(), Float::with_val(calc.context.prec, -&u[k])];
+        product(&lhs, [v[k].clone(), v[j].clone()].into_iter(), &calc.context)
+    }).collect::<Result<Vec<_>, _>>().map(Value::List)
+}
+
+pub fn norm(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a] = values(calc, args)?;
+    let items = match a {
+        Value::Real(x) => vec![x],
+        Value::List(items) => items,
+        a => matrix(a)?.concat(),
+    };
+    let total = product(&items, items.iter().cloned(), &calc.context)?;
+    Ok(Value::Real(total.sqrt()))
+}
+
+pub fn rank(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a] = values(calc, args)?;
+    let rows = match a {
+        Value::List(items) => vec![items],
+        a => matrix(a)?,
+    };
+    let rank = reduce(&rows, rows[0].len(), calc.context.prec).pivots.len();
+    Ok(Value::Real(Float::with_val(calc.context.prec, rank)))
+}
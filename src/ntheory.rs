@@ -0,0 +1,202 @@
+use rug::integer::IsPrime;
+use rug::{Complete, Integer, Rational};
+use crate::{combin, Calculator, CalcError, Context, FloatExt, Value};
+
+const REPS: u32 = 30;
+const TRIAL: u32 = 10_000;
+const MAX_RHO: u32 = 1 << 22;
+
+fn prime(n: &Integer) -> bool {
+    n.is_probably_prime(REPS) != IsPrime::No
+}
+
+fn fold(calc: &Calculator, args: &[&[u8]], f: fn(Integer, &Integer) -> Integer) -> Result<Value, CalcError> {
+    let mut scope = calc.scope(calc.context.prec);
+    let mut values = args.iter().map(|arg| scope.value(arg)?.integer());
+    let first = values.next().ok_or(CalcError::ParameterError)??;
+    values.try_fold(first.abs(), |acc, n| Ok(f(acc, &n?)))
+        .map(Value::Integer)
+}
+
+#[derive(Clone, Copy)]
+enum Rounding {
+    Trunc,
+    Floor,
+    Euclid,
+}
+
+fn remainder(calc: &Calculator, args: &[&[u8]], rounding: Rounding) -> Result<Value, CalcError> {
+    let [a, b] = args else {
+        return Err(CalcError::ParameterError);
+    };
+    let mut scope = calc.scope(calc.context.prec);
+    let (a, b) = (scope.value(a)?, scope.value(b)?);
+    if let (Some(a), Some(b)) = (a.fraction(), b.fraction()) {
+        if b != 0 {
+            let q = Rational::from(&a / &b);
+            let q = match rounding {
+                Rounding::Trunc => q.trunc(),
+                Rounding::Euclid if b < 0 => q.ceil(),
+                Rounding::Floor | Rounding::Euclid => q.floor(),
+            };
+            return Ok(Value::rational(a - q * b));
+        }
+    }
+    let context = &calc.context;
+    let (a, b) = (a.real(context)?, b.real(context)?);
+    if b == 0.0 && context.strict() {
+        return Err(CalcError::DivideByZero);
+    }
+    let r = a.fmod(&b, context.prec);
+    let r = match rounding {
+        Rounding::Floor if r != 0.0 && r.is_sign_negative() != b.is_sign_negative() => r + &b,
+        Rounding::Euclid if r < 0.0 => r + b.abs(),
+        _ => r,
+    };
+    r.accuracy(context).map(Value::Real)
+}
+
+pub fn modulo(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    remainder(calc, args, Rounding::Floor)
+}
+
+pub fn rem(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    remainder(calc, args, Rounding::Trunc)
+}
+
+pub fn emod(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    remainder(calc, args, Rounding::Euclid)
+}
+
+pub fn gcd(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    fold(calc, args, Integer::gcd)
+}
+
+pub fn lcm(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    fold(calc, args, Integer::lcm)
+}
+
+pub fn isprime(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = combin::integers(calc, args)?;
+    Ok(Value::Integer(Integer::from(u8::from(n >= 2 && prime(&n)))))
+}
+
+pub fn nextprime(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = combin::integers(calc, args)?;
+    Ok(Value::Integer(n.next_prime()))
+}
+
+pub fn prevprime(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [mut n] = combin::integers(calc, args)?;
+    if n <= 2 {
+        return Err(CalcError::ParameterError);
+    }
+    n -= 1;
+    while !prime(&n) {
+        calc.context.check()?;
+        n -= 1;
+    }
+    Ok(Value::Integer(n))
+}
+
+fn rho(n: &Integer, context: &Context) -> Result<Integer, CalcError> {
+    let mut spent = 0;
+    for seed in 1u32.. {
+        let step = |x: &Integer| (x.square_ref().complete() + seed) % n;
+        let (mut x, mut y) = (Integer::from(2), Integer::from(2));
+        while spent < MAX_RHO {
+            context.check()?;
+            spent += 1;
+            x = step(&x);
+            y = step(&step(&y));
+            let divisor = (&x - &y).complete().gcd(n);
+            if divisor == *n {
+                break;
+            } else if divisor != 1 {
+                return Ok(divisor);
+            }
+        }
+        if spent >= MAX_RHO {
+            break;
+        }
+    }
+    Err(CalcError::LimitExceeded)
+}
+
+fn factorize(n: &Integer, context: &Context) -> Result<Vec<(Integer, u32)>, CalcError> {
+    let mut rest = n.clone().abs();
+    let mut factors = Vec::new();
+    let mut p = Integer::from(2);
+    while p < TRIAL && rest > 1 {
+        let (next, exp) = rest.remove_factor(&p);
+        rest = next;
+        if exp > 0 {
+            factors.push((p.clone(), exp));
+        }
+        p = p.next_prime();
+    }
+    let mut pending = vec![rest];
+    while let Some(m) = pending.pop() {
+        if m == 1 {
+            continue;
+        } else if prime(&m) {
+            match factors.iter_mut().find(|(p, _)| *p == m) {
+                Some((_, exp)) => *exp += 1,
+                None => factors.push((m, 1)),
+            }
+            continue;
+        }
+        let divisor = rho(&m, context)?;
+        pending.push(m / &divisor);
+        pending.push(divisor);
+    }
+    factors.sort();
+    Ok(factors)
+}
+
+pub fn factor(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = combin::integers(calc, args)?;
+    if n == 0 {
+        return Err(CalcError::ParameterError);
+    }
+    let mut factors = factorize(&n, &calc.context)?;
+    if n < 0 {
+        factors.insert(0, (Integer::from(-1), 1));
+    }
+    Ok(Value::Factors(factors))
+}
+
+pub fn totient(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [n] = combin::integers(calc, args)?;
+    if n <= 0 {
+        return Err(CalcError::ParameterError);
+    }
+    let phi = factorize(&n, &calc.context)?.into_iter().fold(n, |phi, (p, _)| {
+        phi / &p * (p - 1u32)
+    });
+    Ok(Value::Integer(phi))
+}
+
+pub fn modpow(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [b, e, m] = combin::integers(calc, args)?;
+    if m <= 0 {
+        return Err(CalcError::ParameterError);
+    }
+    b.pow_mod(&e, &m).map(Value::Integer).map_err(|_| CalcError::ParameterError)
+}
+
+pub fn modinv(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, m] = combin::integers(calc, args)?;
+    if m <= 0 {
+        return Err(CalcError::ParameterError);
+    }
+    a.invert(&m).map(Value::Integer).map_err(|_| CalcError::ParameterError)
+}
+
+pub fn jacobi(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, n] = combin::integers(calc, args)?;
+    if n <= 0 || n.is_even() {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(Value::Integer(Integer::from(a.jacobi(&n))))
+}
@@ -0,0 +1,234 @@
+use rug::Float;
+use rug::float::Constant;
+use std::cmp::{max, Ordering};
+use crate::{matrix, Calculator, CalcError, FloatExt, Value};
+
+const MAX_POLISH: u32 = 64;
+
+type Complex = (Float, Float);
+
+fn mul(a: &Complex, b: &Complex, work: u32) -> Complex {
+    let re = Float::with_val(work, &a.0 * &b.0) - Float::with_val(work, &a.1 * &b.1);
+    let im = Float::with_val(work, &a.0 * &b.1) + Float::with_val(work, &a.1 * &b.0);
+    (re, im)
+}
+
+fn div(a: &Complex, b: &Complex, work: u32) -> Complex {
+    let den = Float::with_val(work, b.0.square_ref()) + Float::with_val(work, b.1.square_ref());
+    let re = Float::with_val(work, &a.0 * &b.0) + Float::with_val(work, &a.1 * &b.1);
+    let im = Float::with_val(work, &a.1 * &b.0) - Float::with_val(work, &a.0 * &b.1);
+    (re / &den, im / den)
+}
+
+fn abs(a: &Complex, work: u32) -> Float {
+    Float::with_val(work, a.0.hypot_ref(&a.1))
+}
+
+fn horner(coef: &[Float], z: &Complex, work: u32) -> (Complex, Complex) {
+    let zero = || Float::with_val(work, 0);
+    let (mut p, mut dp) = ((zero(), zero()), (zero(), zero()));
+    for c in coef {
+        let next = mul(&dp, z, work);
+        dp = (next.0 + &p.0, next.1 + &p.1);
+        let next = mul(&p, z, work);
+        p = (next.0 + c, next.1);
+    }
+    (p, dp)
+}
+
+fn coefficients(value: Value) -> Result<Vec<Float>, CalcError> {
+    let coef = matrix::vector(value)?;
+    let lead = coef.iter().position(|c| *c != 0).unwrap_or(coef.len());
+    if lead == coef.len() {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(coef[lead..].to_vec())
+}
+
+fn derive(coef: &[Float], times: usize, work: u32) -> Vec<Float> {
+    let n = coef.len() - 1;
+    coef[..=n - times].iter().enumerate().map(|(i, c)| {
+        let k = n - i;
+        (k - times + 1..=k).fold(Float::with_val(work, c), |c, j| c * j as u32)
+    }).collect()
+}
+
+fn polish(roots: &[Complex], monic: &[Float], prec: u32, work: u32) -> Vec<Complex> {
+    let near = Float::with_val(work, Float::i_exp(1, -(prec as i32) / 2));
+    let tol = Float::with_val(work, Float::i_exp(1, -(work as i32) + 8));
+    let mut seen = vec![false; roots.len()];
+    let mut polished = Vec::with_capacity(roots.len());
+    for (k, z) in roots.iter().enumerate() {
+        if seen[k] {
+            continue;
+        }
+        let scale = abs(z, work).max(&Float::with_val(work, 1));
+        let group: Vec<usize> = (k..roots.len()).filter(|&j| {
+            let gap = (Float::with_val(work, &z.0 - &roots[j].0), Float::with_val(work, &z.1 - &roots[j].1));
+            !seen[j] && abs(&gap, work) <= Float::with_val(work, &near * &scale)
+        }).collect();
+        group.iter().for_each(|&j| seen[j] = true);
+        let count = group.len() as u32;
+        let mut center = (
+            Float::with_val(work, Float::sum(group.iter().map(|&j| &roots[j].0))) / count,
+            Float::with_val(work, Float::sum(group.iter().map(|&j| &roots[j].1))) / count,
+        );
+        if group.len() > 1 {
+            let coef = derive(monic, group.len() - 1, work);
+            for _ in 0..MAX_POLISH {
+                let (p, dp) = horner(&coef, &center, work);
+                let step = div(&p, &dp, work);
+                if !step.0.is_finite() || !step.1.is_finite() {
+                    break;
+                }
+                center = (center.0 - &step.0, center.1 - &step.1);
+                if abs(&step, work) <= Float::with_val(work, &tol * &scale) {
+                    break;
+                }
+            }
+        }
+        polished.extend(group.iter().map(|_| center.clone()));
+    }
+    polished
+}
+
+fn aberth(coef: &[Float], prec: u32, work: u32) -> Result<Vec<Complex>, CalcError> {
+    let n = coef.len() - 1;
+    let monic: Vec<Float> = coef.iter().map(|c| Float::with_val(work, c / &coef[0])).collect();
+    let radius = monic[1..].iter().fold(Float::with_val(work, 0), |r, c| {
+        r.max(&Float::with_val(work, c.abs_ref()))
+    }) + 1u32;
+    let turn = Float::with_val(work, Constant::Pi) * 2u32 / n as u32;
+    let mut roots: Vec<Complex> = (0..n).map(|k| {
+        let angle = Float::with_val(work, &turn * k as u32) + 0.4f64;
+        let (sin, cos) = angle.sin_cos(Float::new(work));
+        (cos * &radius, sin * &radius)
+    }).collect();
+    let tol = Float::with_val(work, Float::i_exp(1, -(work as i32) + 8));
+    let limit = max(500, 2 * work);
+    for _ in 0..limit {
+        let mut settled = true;
+        for k in 0..n {
+            let (p, dp) = horner(&monic, &roots[k], work);
+            let bound = monic.iter().fold(Float::with_val(work, 0), |b, c| {
+                b * abs(&roots[k], work) + Float::with_val(work, c.abs_ref())
+            }) * &tol;
+            if abs(&p, work) <= bound {
+                continue;
+            }
+            settled = false;
+            let ratio = div(&p, &dp, work);
+            let mut sum = (Float::with_val(work, 0), Float::with_val(work, 0));
+            let one = (Float::with_val(work, 1), Float::with_val(work, 0));
+            for (_, root) in roots.iter().enumerate().filter(|&(j, _)| j != k) {
+                let diff = (Float::with_val(work, &roots[k].0 - &root.0), Float::with_val(work, &roots[k].1 - &root.1));
+                let inv = div(&one, &diff, work);
+                sum = (sum.0 + inv.0, sum.1 + inv.1);
+            }
+            let scaled = mul(&ratio, &sum, work);
+            let den = (Float::with_val(work, 1) - scaled.0, -scaled.1);
+            let step = div(&ratio, &den, work);
+            if !step.0.is_finite() || !step.1.is_finite() {
+                return Err(CalcError::NoConvergence);
+            }
+            roots[k].0 -= step.0;
+            roots[k].1 -= step.1;
+        }
+        if settled {
+            roots = polish(&roots, &monic, prec, work);
+            let tiny = Float::with_val(work, Float::i_exp(1, -(prec as i32)));
+            for root in roots.iter_mut() {
+                let scale = abs(root, work).max(&Float::with_val(work, 1)) * &tiny;
+                if Float::with_val(work, root.0.abs_ref()) <= scale {
+                    root.0 = Float::with_val(work, 0);
+                }
+                if Float::with_val(work, root.1.abs_ref()) <= scale {
+                    root.1 = Float::with_val(work, 0);
+                }
+            }
+            return Ok(roots);
+        }
+    }
+    Err(CalcError::NoConvergence)
+}
+
+pub fn roots(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [p] = matrix::values(calc, args)?;
+    let coef = coefficients(p)?;
+    let prec = calc.context.prec;
+    let work = prec * 2 + 64;
+    let zeros = coef.iter().rev().take_while(|c| **c == 0).count();
+    let coef = &coef[..coef.len() - zeros];
+    let mut roots = if coef.len() > 1 {
+        aberth(coef, prec, work)?
+    } else { Vec::new() };
+    roots.extend((0..zeros).map(|_| (Float::with_val(work, 0), Float::with_val(work, 0))));
+    let round = |x: Float| Float::with_val(prec, x).accuracy(&calc.context);
+    let mut roots = roots.into_iter().map(|(re, im)| Ok((round(re)?, round(im)?)))
+        .collect::<Result<Vec<_>, CalcError>>()?;
+    roots.sort_by(|a, b| {
+        (a.1 != 0).cmp(&(b.1 != 0))
+            .then(a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+            .then(a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+    });
+    if roots.iter().all(|root| root.1 == 0) {
+        return Ok(Value::List(roots.into_iter().map(|root| root.0).collect()));
+    }
+    Ok(Value::Complex(roots))
+}
+
+fn evaluate(coef: &[Float], x: &Float, prec: u32) -> Float {
+    coef.iter().fold(Float::with_val(prec + 32, 0), |acc, c| acc * x + c)
+}
+
+pub fn polyval(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [p, x] = matrix::values(calc, args)?;
+    let coef = matrix::vector(p)?;
+    let prec = calc.context.prec;
+    let round = |x: &Float| Float::with_val(prec, evaluate(&coef, x, prec)).accuracy(&calc.context);
+    match x {
+        Value::Real(x) => Ok(Value::Real(round(&x)?)),
+        Value::List(items) => items.iter().map(round)
+            .collect::<Result<Vec<_>, _>>().map(Value::List),
+        Value::Quantity(..) => Err(CalcError::DimensionMismatch),
+        _ => Err(CalcError::ParameterError),
+    }
+}
+
+pub fn polyder(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [p] = matrix::values(calc, args)?;
+    let coef = matrix::vector(p)?;
+    let n = coef.len().saturating_sub(1);
+    if n == 0 {
+        return Ok(Value::List(vec![Float::with_val(calc.context.prec, 0)]));
+    }
+    coef[..n].iter().enumerate().map(|(i, c)| {
+        Float::with_val(calc.context.prec, c * (n - i) as u32).accuracy(&calc.context)
+    }).collect::<Result<Vec<_>, _>>().map(Value::List)
+}
+
+pub fn polyfit(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [xs, ys, deg] = matrix::values(calc, args)?;
+    let (xs, ys, deg) = (matrix::vector(xs)?, matrix::vector(ys)?, deg.real(&calc.context)?);
+    if xs.len() != ys.len() || !deg.is_integer() || deg < 0 || deg >= xs.len() as u32 {
+        return Err(CalcError::ParameterError);
+    }
+    let n = deg.to_u32_saturating().unwrap_or(0) as usize + 1;
+    let work = calc.context.prec + 64;
+    let powers: Vec<Vec<Float>> = xs.iter().map(|x| {
+        let mut row = vec![Float::with_val(work, 1)];
+        for i in 1..n {
+            row.push(Float::with_val(work, &row[i - 1] * x));
+        }
+        row.reverse();
+        row
+    }).collect();
+    let total = |terms: Vec<Float>| Float::with_val(work, Float::sum(terms.iter()));
+    let normal = (0..n).map(|i| (0..n).map(|j| {
+        total(powers.iter().map(|row| Float::with_val(work, &row[i] * &row[j])).collect())
+    }).collect()).collect();
+    let rhs = (0..n).map(|i| {
+        vec![total(powers.iter().zip(&ys).map(|(row, y)| Float::with_val(work, &row[i] * y)).collect())]
+    }).collect();
+    Ok(Value::List(matrix::system(calc, normal, rhs)?.concat()))
+}
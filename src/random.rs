@@ -0,0 +1,77 @@
+use rug::rand::RandState;
+use rug::{Float, Integer};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex};
+use crate::{combin, matrix, Calculator, CalcError, FloatExt, Value};
+
+pub struct Random {
+    state: RandState<'static>,
+    pub draws: u64,
+}
+
+impl Random {
+    pub fn shared() -> Arc<Mutex<Random>> {
+        let mut state = RandState::new();
+        state.seed(&Integer::from(RandomState::new().build_hasher().finish()));
+        Arc::new(Mutex::new(Random { state, draws: 0 }))
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.state.seed(&Integer::from(seed));
+    }
+
+    fn draw(&mut self) -> &mut RandState<'static> {
+        self.draws += 1;
+        &mut self.state
+    }
+}
+
+fn blank(args: &[&[u8]]) -> bool {
+    args.iter().all(|arg| arg.trim_ascii().is_empty())
+}
+
+pub fn rand(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    if !blank(args) {
+        return Err(CalcError::ParameterError);
+    }
+    let mut random = calc.random();
+    Ok(Value::Real(Float::with_val(calc.context.prec, Float::random_bits(random.draw()))))
+}
+
+pub fn randint(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, b] = combin::integers(calc, args)?;
+    if a > b {
+        return Err(CalcError::ParameterError);
+    }
+    let span = Integer::from(&b - &a) + 1u32;
+    let mut random = calc.random();
+    Ok(Value::Integer(span.random_below(random.draw()) + a))
+}
+
+pub fn randn(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let prec = calc.context.prec;
+    let (mu, sigma) = if blank(args) {
+        (Float::with_val(prec, 0), Float::with_val(prec, 1))
+    } else {
+        let [mu, sigma] = matrix::values(calc, args)?;
+        (mu.real(&calc.context)?, sigma.real(&calc.context)?)
+    };
+    if sigma < 0 {
+        return Err(CalcError::ParameterError);
+    }
+    let mut random = calc.random();
+    let z = Float::with_val(prec, Float::random_normal(random.draw()));
+    Float::with_val(prec, z * sigma + mu).accuracy(&calc.context).map(Value::Real)
+}
+
+pub fn choose(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [list] = matrix::values(calc, args)?;
+    let items = matrix::vector(list)?;
+    if items.is_empty() {
+        return Err(CalcError::ParameterError);
+    }
+    let mut random = calc.random();
+    let index = Integer::from(items.len()).random_below(random.draw());
+    Ok(Value::Real(items[index.to_usize().unwrap_or(0)].clone()))
+}
@@ -0,0 +1,119 @@
+use rug::Float;
+use rug::ops::Pow;
+use std::cmp::max;
+use crate::{identifier, stats, Calculator, CalcError, FloatExt, Value};
+
+const MAX_TERMS: u32 = 1_000_000;
+
+fn bound(value: Float) -> Result<Float, CalcError> {
+    if value.is_integer() {
+        Ok(value)
+    } else { Err(CalcError::ParameterError) }
+}
+
+fn levin(partials: &[Float], terms: &[Float], work: u32) -> Option<Float> {
+    let k = terms.len() as u32 - 1;
+    let last = Float::with_val(work, k + 1);
+    let mut num = Float::with_val(work, 0);
+    let mut den = Float::with_val(work, 0);
+    let mut binom = Float::with_val(work, 1);
+    for (j, (partial, term)) in partials.iter().zip(terms).enumerate() {
+        let j = j as u32;
+        let omega = Float::with_val(work, term * (j + 1));
+        let ratio = Float::with_val(work, j + 1) / &last;
+        let coef = Float::with_val(work, &binom * ratio.pow(k.saturating_sub(1))) / omega;
+        if j.is_multiple_of(2) {
+            num += Float::with_val(work, &coef * partial);
+            den += coef;
+        } else {
+            num -= Float::with_val(work, &coef * partial);
+            den -= coef;
+        }
+        binom = binom * (k - j) / (j + 1);
+    }
+    if den == 0 {
+        None
+    } else { Some(num / den) }
+}
+
+fn infinite(
+    scope: &mut Calculator, body: &[u8], var: &[u8], from: Float, product: bool, prec: u32
+) -> Result<Float, CalcError> {
+    let work = scope.context.prec;
+    let limit = max(1024, prec);
+    let tol = Float::with_val(work, Float::i_exp(1, -(prec as i32)));
+    let mut total = Float::with_val(work, if product { 1 } else { 0 });
+    let mut partials = Vec::new();
+    let mut terms = Vec::new();
+    let mut last: Option<Float> = None;
+    let mut check = 16;
+    let mut k = from;
+    while partials.len() < limit as usize {
+        scope.bind(var, Value::Real(k.clone()));
+        let value = scope.evaluate(body)?;
+        let term = if product {
+            let next = Float::with_val(work, &total * &value);
+            Float::with_val(work, &next - &total)
+        } else { value };
+        total += &term;
+        partials.push(total.clone());
+        terms.push(term);
+        k += 1;
+        if partials.len() < check {
+            continue;
+        }
+        check += max(16, check / 8);
+        let estimate = if terms.iter().any(|term| *term == 0) {
+            Some(total.clone())
+        } else { levin(&partials, &terms, work) };
+        if let (Some(last), Some(estimate)) = (&last, &estimate) {
+            let scale = Float::with_val(work, estimate.abs_ref()).max(&Float::with_val(work, 1));
+            if Float::with_val(work, estimate - last).abs() <= Float::with_val(work, &tol * &scale) {
+                return Ok(estimate.clone());
+            }
+        }
+        last = estimate;
+    }
+    Err(CalcError::NoConvergence)
+}
+
+fn series(calc: &Calculator, args: &[&[u8]], product: bool) -> Result<Value, CalcError> {
+    if args.len() != 4 {
+        return Err(CalcError::ParameterError);
+    }
+    let (var, body) = (identifier(args[0])?, args[3]);
+    let prec = calc.context.prec;
+    let endless = args[2].trim_ascii() == b"inf";
+    let work = if endless { prec * 2 + 64 } else { prec + 64 };
+    let mut scope = calc.scope(work);
+    let from = bound(scope.evaluate(args[1])?)?;
+    if endless {
+        let value = infinite(&mut scope, body, var, from, product, prec)?;
+        return Ok(Value::Real(Float::with_val(prec, value).accuracy(&calc.context)?));
+    }
+    let to = bound(scope.evaluate(args[2])?)?;
+    if Float::with_val(work, &to - &from) >= MAX_TERMS {
+        return Err(CalcError::LimitExceeded);
+    }
+    let mut total = Float::with_val(work, if product { 1 } else { 0 });
+    let mut k = from;
+    while k <= to {
+        scope.bind(var, Value::Real(k.clone()));
+        let term = scope.evaluate(body)?;
+        if product {
+            total *= term;
+        } else { total += term; }
+        k += 1;
+    }
+    Ok(Value::Real(Float::with_val(prec, total).accuracy(&calc.context)?))
+}
+
+pub fn sum(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    if args.len() == 4 && identifier(args[0]).is_ok() {
+        series(calc, args, false)
+    } else { stats::sum(calc, args) }
+}
+
+pub fn prod(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    series(calc, args, true)
+}
@@ -0,0 +1,306 @@
+use rug::Float;
+use rug::float::{Constant, Special};
+use std::cmp::Ordering;
+use crate::{matrix, Calculator, CalcError, Context, FloatExt, Value};
+
+const MAX_TERMS: u32 = 100_000;
+
+fn tiny(work: u32) -> Float {
+    Float::with_val(work, Float::i_exp(1, -(work as i32) * 2))
+}
+
+fn eps(work: u32) -> Float {
+    Float::with_val(work, Float::i_exp(1, 8 - work as i32))
+}
+
+fn guard(x: Float, work: u32) -> Float {
+    let tiny = tiny(work);
+    if Float::with_val(work, x.abs_ref()) < tiny { tiny } else { x }
+}
+
+fn fraction(a: &Float, x: &Float, work: u32) -> bool {
+    *x >= Float::with_val(work, a + 1u32) && *x >= work / 8
+}
+
+fn gamma_series(a: &Float, x: &Float, work: u32) -> Result<Float, CalcError> {
+    let eps = eps(work);
+    let mut term = Float::with_val(work, 1);
+    let mut sum = Float::with_val(work, 1);
+    let mut n = Float::with_val(work, a);
+    for _ in 0..MAX_TERMS {
+        n += 1u32;
+        term = term * x / &n;
+        sum += &term;
+        if Float::with_val(work, term.abs_ref()) <= Float::with_val(work, &sum * &eps) {
+            let log = Float::with_val(work, a * Float::with_val(work, x.ln_ref()))
+                - x - Float::with_val(work, a + 1u32).ln_gamma();
+            return Ok(log.exp() * sum);
+        }
+    }
+    Err(CalcError::NoConvergence)
+}
+
+fn gamma_fraction(a: &Float, x: &Float, work: u32) -> Result<Float, CalcError> {
+    let eps = eps(work);
+    let mut b = Float::with_val(work, x + 1u32) - a;
+    let mut c = Float::with_val(work, 1) / tiny(work);
+    let mut d = Float::with_val(work, 1) / &b;
+    let mut h = d.clone();
+    for i in 1..MAX_TERMS {
+        let an = Float::with_val(work, a - i) * i;
+        b += 2u32;
+        d = Float::with_val(work, 1) / guard(Float::with_val(work, &an * &d) + &b, work);
+        c = guard(Float::with_val(work, &an / &c) + &b, work);
+        let delta = Float::with_val(work, &d * &c);
+        h *= &delta;
+        if Float::with_val(work, &delta - 1u32).abs() <= eps {
+            let log = Float::with_val(work, a * Float::with_val(work, x.ln_ref()))
+                - x - Float::with_val(work, a.ln_gamma_ref());
+            return Ok(log.exp() * h);
+        }
+    }
+    Err(CalcError::NoConvergence)
+}
+
+pub fn gamma_p(a: &Float, x: &Float, work: u32) -> Result<Float, CalcError> {
+    if a.is_nan() || x.is_nan() {
+        return Ok(Float::with_val(work, Special::Nan));
+    } else if *a <= 0 || *x < 0 {
+        return Err(CalcError::ParameterError);
+    } else if *x == 0 {
+        return Ok(Float::with_val(work, 0));
+    }
+    if fraction(a, x, work) {
+        Ok(1u32 - gamma_fraction(a, x, work)?)
+    } else { gamma_series(a, x, work) }
+}
+
+pub fn gamma_q(a: &Float, x: &Float, work: u32) -> Result<Float, CalcError> {
+    if a.is_nan() || x.is_nan() {
+        return Ok(Float::with_val(work, Special::Nan));
+    } else if *a <= 0 || *x < 0 {
+        return Err(CalcError::ParameterError);
+    } else if *x == 0 {
+        return Ok(Float::with_val(work, 1));
+    }
+    if fraction(a, x, work) {
+        gamma_fraction(a, x, work)
+    } else { Ok(1u32 - gamma_series(a, x, work)?) }
+}
+
+pub fn ln_beta(a: &Float, b: &Float, work: u32) -> Float {
+    Float::with_val(work, a.ln_gamma_ref()) + Float::with_val(work, b.ln_gamma_ref())
+        - Float::with_val(work, a + b).ln_gamma()
+}
+
+fn beta_fraction(a: &Float, b: &Float, x: &Float, work: u32) -> Result<Float, CalcError> {
+    let eps = eps(work);
+    let one = Float::with_val(work, 1);
+    let sum = Float::with_val(work, a + b);
+    let mut c = one.clone();
+    let mut d = Float::with_val(work, 1) / guard(1u32 - Float::with_val(work, &sum * x) / (a + one.clone()), work);
+    let mut h = d.clone();
+    for m in 1..MAX_TERMS {
+        let m2 = Float::with_val(work, a + 2 * m);
+        let even = Float::with_val(work, b - m) * m * x
+            / (Float::with_val(work, &m2 - 1u32) * &m2);
+        d = Float::with_val(work, 1) / guard(Float::with_val(work, &even * &d) + 1u32, work);
+        c = guard(Float::with_val(work, &even / &c) + 1u32, work);
+        h *= Float::with_val(work, &d * &c);
+        let odd = -(Float::with_val(work, a + m) * Float::with_val(work, &sum + m) * x)
+            / (Float::with_val(work, &m2 + 1u32) * &m2);
+        d = Float::with_val(work, 1) / guard(Float::with_val(work, &odd * &d) + 1u32, work);
+        c = guard(Float::with_val(work, &odd / &c) + 1u32, work);
+        let delta = Float::with_val(work, &d * &c);
+        h *= &delta;
+        if Float::with_val(work, &delta - 1u32).abs() <= eps {
+            let log = Float::with_val(work, a * Float::with_val(work, x.ln_ref()))
+                + Float::with_val(work, b * (-Float::with_val(work, x)).ln_1p())
+                - ln_beta(a, b, work);
+            return Ok(log.exp() * h / a);
+        }
+    }
+    Err(CalcError::NoConvergence)
+}
+
+pub fn beta_inc(a: &Float, b: &Float, x: &Float, work: u32) -> Result<Float, CalcError> {
+    if a.is_nan() || b.is_nan() || x.is_nan() {
+        return Ok(Float::with_val(work, Special::Nan));
+    } else if *a <= 0 || *b <= 0 || *x < 0 || *x > 1 {
+        return Err(CalcError::ParameterError);
+    } else if *x == 0 || *x == 1 {
+        return Ok(Float::with_val(work, x));
+    }
+    let split = Float::with_val(work, a + 1u32) / (Float::with_val(work, a + b) + 2u32);
+    if *x < split {
+        beta_fraction(a, b, x, work)
+    } else {
+        let y = Float::with_val(work, 1u32 - x);
+        Ok(1u32 - beta_fraction(b, a, &y, work)?)
+    }
+}
+
+fn result(calc: &Calculator, value: Float) -> Result<Value, CalcError> {
+    Ok(Value::Real(Float::with_val(calc.context.prec, value).accuracy(&calc.context)?))
+}
+
+fn pole(x: &Float) -> bool {
+    *x <= 0 && x.is_integer()
+}
+
+fn order(calc: &Calculator, args: &[&[u8]]) -> Result<(i32, Float), CalcError> {
+    let [n, x] = matrix::values(calc, args)?;
+    let n = n.integer()?.to_i32().ok_or(CalcError::LimitExceeded)?;
+    Ok((n, x.real(&calc.context)?))
+}
+
+pub fn besselj(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (n, x) = order(calc, args)?;
+    result(calc, x.jn(n))
+}
+
+pub fn bessely(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (n, x) = order(calc, args)?;
+    if x <= 0 {
+        return Err(CalcError::DomainError);
+    }
+    result(calc, x.yn(n))
+}
+
+pub fn agm(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, b] = matrix::values(calc, args)?;
+    let (a, b) = (a.real(&calc.context)?, b.real(&calc.context)?);
+    if a < 0 || b < 0 {
+        return Err(CalcError::DomainError);
+    }
+    result(calc, a.agm(&b))
+}
+
+pub fn hypot(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, b] = matrix::values(calc, args)?;
+    let (a, b) = (a.real(&calc.context)?, b.real(&calc.context)?);
+    result(calc, a.hypot(&b))
+}
+
+pub fn beta(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, b] = matrix::values(calc, args)?;
+    let (a, b) = (a.real(&calc.context)?, b.real(&calc.context)?);
+    if pole(&a) || pole(&b) {
+        return Err(CalcError::DomainError);
+    }
+    let scale = [&a, &b].iter().filter_map(|v| v.get_exp()).max().unwrap_or(0).max(0);
+    let work = calc.context.prec + 64 + scale as u32;
+    let (a, b) = (Float::with_val(work, a), Float::with_val(work, b));
+    let sum = Float::with_val(work, &a + &b);
+    if pole(&sum) {
+        return result(calc, Float::with_val(work, 0));
+    }
+    let (la, sa) = a.ln_abs_gamma();
+    let (lb, sb) = b.ln_abs_gamma();
+    let (lab, sab) = sum.ln_abs_gamma();
+    let value = (la + lb - lab).exp();
+    let negative = [sa, sb, sab].iter().filter(|&&s| s == Ordering::Less).count() % 2 == 1;
+    result(calc, if negative { -value } else { value })
+}
+
+pub fn gammainc(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let [a, x] = matrix::values(calc, args)?;
+    let (a, x) = (a.real(&calc.context)?, x.real(&calc.context)?);
+    if x < 0 || (x == 0 && a <= 0) {
+        return Err(CalcError::DomainError);
+    }
+    result(calc, a.gamma_inc(&x))
+}
+
+fn lambert(x: &Float, lower: bool, work: u32) -> Result<Float, CalcError> {
+    let q = Float::with_val(work, Float::with_val(work, 1).exp() * x) + 1u32;
+    if q < 0 || (lower && *x >= 0) {
+        return Err(CalcError::DomainError);
+    } else if q == 0 {
+        return Ok(Float::with_val(work, -1));
+    }
+    let mut w = if *x < -0.25 {
+        let p = Float::with_val(work, q * 2u32).sqrt();
+        let p = if lower { -p } else { p };
+        let p2 = Float::with_val(work, p.square_ref());
+        Float::with_val(work, &p - 1u32) - Float::with_val(work, &p2 / 3u32)
+            + Float::with_val(work, p2 * &p) * 11u32 / 72u32
+    } else if lower || *x > 3 {
+        let l1 = Float::with_val(work, if lower { -x.clone() } else { x.clone() }).ln();
+        let l2 = Float::with_val(work, if lower { -l1.clone() } else { l1.clone() }).ln();
+        l1 - l2
+    } else { Float::with_val(work, x.ln_1p_ref()) };
+    let eps = eps(work);
+    for _ in 0..MAX_TERMS {
+        let ew = Float::with_val(work, w.exp_ref());
+        let f = Float::with_val(work, &w * &ew) - x;
+        let w1 = Float::with_val(work, &w + 1u32);
+        let bend = Float::with_val(work, &w + 2u32) * &f / Float::with_val(work, &w1 * 2u32);
+        let step = f / (ew * w1 - bend);
+        w -= &step;
+        if step.abs() <= (Float::with_val(work, w.abs_ref()) + 1u32) * &eps {
+            return Ok(w);
+        }
+    }
+    Err(CalcError::NoConvergence)
+}
+
+pub fn lambertw(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let (x, lower) = match args.len() {
+        1 => {
+            let [x] = matrix::values(calc, args)?;
+            (x.real(&calc.context)?, false)
+        }
+        _ => {
+            let [x, k] = matrix::values(calc, args)?;
+            let lower = match k.integer()?.to_i32() {
+                Some(0) => false,
+                Some(-1) => true,
+                _ => return Err(CalcError::ParameterError),
+            };
+            (x.real(&calc.context)?, lower)
+        }
+    };
+    let work = calc.context.prec + 32;
+    result(calc, lambert(&Float::with_val(work, x), lower, work)?)
+}
+
+pub fn ellipk(m: Float, context: &Context) -> Result<Float, CalcError> {
+    if context.strict() && m >= 1 {
+        return Err(CalcError::DomainError);
+    }
+    let work = context.prec + 32;
+    let b = Float::with_val(work, 1u32 - m).sqrt();
+    let mean = Float::with_val(work, 1).agm(&b);
+    Float::with_val(context.prec, Float::with_val(work, Constant::Pi) / (mean * 2u32))
+        .accuracy(context)
+}
+
+pub fn ellipe(m: Float, context: &Context) -> Result<Float, CalcError> {
+    if m > 1 || m.is_nan() {
+        return if context.strict() {
+            Err(CalcError::DomainError)
+        } else { Ok(Float::with_val(context.prec, Special::Nan)) };
+    } else if m == 1 {
+        return Ok(Float::with_val(context.prec, 1));
+    }
+    let work = context.prec + 32;
+    let eps = eps(work);
+    let mut a = Float::with_val(work, 1);
+    let mut b = Float::with_val(work, 1u32 - &m).sqrt();
+    let mut sum = Float::with_val(work, &m / 2u32);
+    let mut power = Float::with_val(work, 0.5);
+    for _ in 0..MAX_TERMS {
+        let c = Float::with_val(work, &a - &b) / 2u32;
+        if Float::with_val(work, c.abs_ref()) <= Float::with_val(work, &a * &eps) {
+            let scale = Float::with_val(work, Constant::Pi) / (a * 2u32);
+            return Float::with_val(context.prec, scale * (1u32 - sum)).accuracy(context);
+        }
+        let next = Float::with_val(work, &a + &b) / 2u32;
+        b = Float::with_val(work, &a * &b).sqrt();
+        a = next;
+        power *= 2u32;
+        sum += Float::with_val(work, c.square_ref()) * &power;
+    }
+    Err(CalcError::NoConvergence)
+}
@@ -0,0 +1,139 @@
+use rug::Float;
+use std::cmp::Ordering;
+use crate::{Calculator, CalcError, FloatExt, Value};
+
+fn samples(calc: &Calculator, args: &[&[u8]]) -> Result<Vec<Float>, CalcError> {
+    let mut scope = calc.scope(calc.context.prec);
+    let mut data = Vec::new();
+    for arg in args {
+        match scope.value(arg)?.widen(&calc.context)? {
+            Value::Real(value) => data.push(value),
+            Value::List(items) => data.extend(items),
+            Value::Matrix(rows) => data.extend(rows.into_iter().flatten()),
+            Value::Quantity(..) => return Err(CalcError::DimensionMismatch),
+            _ => return Err(CalcError::ParameterError),
+        }
+    }
+    if data.is_empty() || data.iter().any(Float::is_nan) {
+        return Err(CalcError::ParameterError);
+    }
+    Ok(data)
+}
+
+fn sorted(calc: &Calculator, args: &[&[u8]]) -> Result<Vec<Float>, CalcError> {
+    let mut data = samples(calc, args)?;
+    data.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Ok(data)
+}
+
+fn result(calc: &Calculator, value: Float) -> Result<Value, CalcError> {
+    Ok(Value::Real(value.accuracy(&calc.context)?))
+}
+
+fn total(data: &[Float], prec: u32) -> Float {
+    Float::with_val(prec, Float::sum(data.iter()))
+}
+
+fn average(data: &[Float], prec: u32) -> Float {
+    total(data, prec) / data.len() as u32
+}
+
+fn variance(calc: &Calculator, args: &[&[u8]], sample: bool) -> Result<Float, CalcError> {
+    let prec = calc.context.prec;
+    let data = samples(calc, args)?;
+    let count = data.len() as u32 - u32::from(sample);
+    if count == 0 {
+        return Err(CalcError::ParameterError);
+    }
+    let mean = average(&data, prec + 32);
+    let squares = data.iter().map(|x| Float::with_val(prec + 32, x - &mean).square());
+    let squares: Vec<Float> = squares.collect();
+    Ok(Float::with_val(prec, total(&squares, prec + 32) / count))
+}
+
+pub fn sum(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let data = samples(calc, args)?;
+    result(calc, total(&data, calc.context.prec))
+}
+
+pub fn mean(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let data = samples(calc, args)?;
+    result(calc, average(&data, calc.context.prec))
+}
+
+pub fn median(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let data = sorted(calc, args)?;
+    let half = data.len() / 2;
+    if data.len() % 2 == 1 {
+        return result(calc, data[half].clone());
+    }
+    result(calc, total(&data[half - 1..=half], calc.context.prec) / 2u32)
+}
+
+pub fn mode(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let data = sorted(calc, args)?;
+    let (mut best, mut most) = (&data[0], 0);
+    for run in data.chunk_by(|a, b| a == b) {
+        if run.len() > most {
+            (best, most) = (&run[0], run.len());
+        }
+    }
+    result(calc, best.clone())
+}
+
+pub fn var(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    result(calc, variance(calc, args, true)?)
+}
+
+pub fn pvar(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    result(calc, variance(calc, args, false)?)
+}
+
+pub fn stdev(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    result(calc, variance(calc, args, true)?.sqrt())
+}
+
+pub fn pstdev(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    result(calc, variance(calc, args, false)?.sqrt())
+}
+
+pub fn min(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let data = sorted(calc, args)?;
+    result(calc, data[0].clone())
+}
+
+pub fn max(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let data = sorted(calc, args)?;
+    result(calc, data[data.len() - 1].clone())
+}
+
+pub fn percentile(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let prec = calc.context.prec;
+    let (p, args) = match args.split_last() {
+        Some((p, args)) if !args.is_empty() => (p, args),
+        _ => return Err(CalcError::ParameterError),
+    };
+    let p = calc.scope(prec).evaluate(p)?;
+    if !(0..=100).contains(&p) {
+        return Err(CalcError::ParameterError);
+    }
+    let data = sorted(calc, args)?;
+    let rank = Float::with_val(prec + 32, (data.len() - 1) as u32) * p / 100u32;
+    let index = Float::with_val(prec, rank.floor_ref()).to_u32_saturating().unwrap_or(0) as usize;
+    if index + 1 >= data.len() {
+        return result(calc, data[index].clone());
+    }
+    let frac = Float::with_val(prec + 32, rank.fract_ref());
+    let step = Float::with_val(prec + 32, &data[index + 1] - &data[index]) * frac;
+    result(calc, Float::with_val(prec, &data[index] + step))
+}
+
+pub fn geomean(calc: &Calculator, args: &[&[u8]]) -> Result<Value, CalcError> {
+    let prec = calc.context.prec;
+    let data = samples(calc, args)?;
+    if data.iter().any(|x| *x <= 0) {
+        return Err(CalcError::ParameterError);
+    }
+    let logs: Vec<Float> = data.iter().map(|x| Float::with_val(prec + 32, x.ln_ref())).collect();
+    result(calc, Float::with_val(prec, average(&logs, prec + 32).exp()))
+}
@@ -0,0 +1,299 @@
+use rug::Float;
+use rug::ops::Pow;
+use phf::phf_map;
+use phf::Map;
+use crate::CalcError;
+
+const BASE: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+#[derive(Clone, Copy)]
+struct UnitDef {
+    scale: &'static str,
+    offset: Option<&'static str>,
+    dims: [i8; 7],
+    prefix: bool,
+}
+
+const fn unit(scale: &'static str, dims: [i8; 7], prefix: bool) -> UnitDef {
+    UnitDef { scale, offset: None, dims, prefix }
+}
+
+const fn affine(scale: &'static str, offset: &'static str) -> UnitDef {
+    UnitDef { scale, offset: Some(offset), dims: [0, 0, 0, 0, 1, 0, 0], prefix: false }
+}
+
+static UNITS: Map<&'static [u8], UnitDef> = phf_map! {
+    b"m" => unit("1", [1, 0, 0, 0, 0, 0, 0], true),
+    b"inch" => unit("0.0254", [1, 0, 0, 0, 0, 0, 0], false),
+    b"ft" => unit("0.3048", [1, 0, 0, 0, 0, 0, 0], false),
+    b"yd" => unit("0.9144", [1, 0, 0, 0, 0, 0, 0], false),
+    b"mi" => unit("1609.344", [1, 0, 0, 0, 0, 0, 0], false),
+    b"nmi" => unit("1852", [1, 0, 0, 0, 0, 0, 0], false),
+    b"au" => unit("149597870700", [1, 0, 0, 0, 0, 0, 0], false),
+    b"ly" => unit("9460730472580800", [1, 0, 0, 0, 0, 0, 0], false),
+    b"ha" => unit("10000", [2, 0, 0, 0, 0, 0, 0], false),
+    b"acre" => unit("4046.8564224", [2, 0, 0, 0, 0, 0, 0], false),
+    b"l" => unit("0.001", [3, 0, 0, 0, 0, 0, 0], true),
+    b"gal" => unit("0.003785411784", [3, 0, 0, 0, 0, 0, 0], false),
+    b"g" => unit("0.001", [0, 1, 0, 0, 0, 0, 0], true),
+    b"t" => unit("1000", [0, 1, 0, 0, 0, 0, 0], true),
+    b"lb" => unit("0.45359237", [0, 1, 0, 0, 0, 0, 0], false),
+    b"oz" => unit("0.028349523125", [0, 1, 0, 0, 0, 0, 0], false),
+    b"s" => unit("1", [0, 0, 1, 0, 0, 0, 0], true),
+    b"min" => unit("60", [0, 0, 1, 0, 0, 0, 0], false),
+    b"h" => unit("3600", [0, 0, 1, 0, 0, 0, 0], false),
+    b"day" => unit("86400", [0, 0, 1, 0, 0, 0, 0], false),
+    b"week" => unit("604800", [0, 0, 1, 0, 0, 0, 0], false),
+    b"yr" => unit("31557600", [0, 0, 1, 0, 0, 0, 0], false),
+    b"mph" => unit("0.44704", [1, 0, -1, 0, 0, 0, 0], false),
+    b"kn" => unit("1852/3600", [1, 0, -1, 0, 0, 0, 0], false),
+    b"A" => unit("1", [0, 0, 0, 1, 0, 0, 0], true),
+    b"K" => unit("1", [0, 0, 0, 0, 1, 0, 0], true),
+    b"degC" => affine("1", "273.15"),
+    b"degF" => affine("5/9", "459.67"),
+    b"degR" => affine("5/9", "0"),
+    b"mol" => unit("1", [0, 0, 0, 0, 0, 1, 0], true),
+    b"cd" => unit("1", [0, 0, 0, 0, 0, 0, 1], true),
+    b"Hz" => unit("1", [0, 0, -1, 0, 0, 0, 0], true),
+    b"N" => unit("1", [1, 1, -2, 0, 0, 0, 0], true),
+    b"Pa" => unit("1", [-1, 1, -2, 0, 0, 0, 0], true),
+    b"bar" => unit("100000", [-1, 1, -2, 0, 0, 0, 0], true),
+    b"atm" => unit("101325", [-1, 1, -2, 0, 0, 0, 0], false),
+    b"psi" => unit("6894.757293168361", [-1, 1, -2, 0, 0, 0, 0], false),
+    b"mmHg" => unit("133.322387415", [-1, 1, -2, 0, 0, 0, 0], false),
+    b"J" => unit("1", [2, 1, -2, 0, 0, 0, 0], true),
+    b"cal" => unit("4.184", [2, 1, -2, 0, 0, 0, 0], true),
+    b"eV" => unit("1.602176634e-19", [2, 1, -2, 0, 0, 0, 0], true),
+    b"Wh" => unit("3600", [2, 1, -2, 0, 0, 0, 0], true),
+    b"W" => unit("1", [2, 1, -3, 0, 0, 0, 0], true),
+    b"V" => unit("1", [2, 1, -3, -1, 0, 0, 0], true),
+    b"ohm" => unit("1", [2, 1, -3, -2, 0, 0, 0], true),
+};
+
+static PREFIX: Map<&'static [u8], &'static str> = phf_map! {
+    b"Y" => "1e24", b"Z" => "1e21", b"E" => "1e18", b"P" => "1e15",
+    b"T" => "1e12", b"G" => "1e9", b"M" => "1e6", b"k" => "1e3",
+    b"h" => "1e2", b"da" => "1e1", b"d" => "1e-1", b"c" => "1e-2",
+    b"m" => "1e-3", b"u" => "1e-6", b"n" => "1e-9", b"p" => "1e-12",
+    b"f" => "1e-15", b"a" => "1e-18", b"z" => "1e-21", b"y" => "1e-24",
+};
+
+#[derive(Clone, Debug)]
+struct Label {
+    name: String,
+    scale: Float,
+    offset: Option<Float>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Unit {
+    dims: [i8; 7],
+    label: Option<Label>,
+}
+
+fn parse_scale(scale: &str, prec: u32) -> Float {
+    let mut parts = scale.split('/').map(|part| {
+        let valid = Float::parse(part).unwrap();
+        Float::with_val(prec, valid)
+    });
+    let numer = parts.next().unwrap();
+    match parts.next() {
+        Some(denom) => numer / denom,
+        None => numer,
+    }
+}
+
+fn lookup(name: &[u8]) -> Option<(UnitDef, Option<&'static str>)> {
+    if let Some(&def) = UNITS.get(name) {
+        return Some((def, None));
+    }
+    (1..name.len()).find_map(|split| {
+        let (prefix, base) = name.split_at(split);
+        let scale = PREFIX.get(prefix)?;
+        UNITS.get(base).filter(|def| def.prefix)
+            .map(|&def| (def, Some(*scale)))
+    })
+}
+
+impl Unit {
+    pub fn none() -> Self {
+        Unit { dims: [0; 7], label: None }
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        self.dims == [0; 7] && self.label.is_none()
+    }
+
+    pub fn same_dims(&self, other: &Unit) -> bool {
+        self.dims == other.dims
+    }
+
+    pub fn is_affine(&self) -> bool {
+        self.label.as_ref().is_some_and(|label| label.offset.is_some())
+    }
+
+    pub fn base(&self) -> Unit {
+        Unit { dims: self.dims, label: None }
+    }
+
+    pub fn mul(&self, other: &Unit) -> Result<Unit, CalcError> {
+        if self.is_dimensionless() {
+            return Ok(other.clone());
+        } else if other.is_dimensionless() {
+            return Ok(self.clone());
+        }
+        let mut dims = self.dims;
+        for (dim, add) in dims.iter_mut().zip(other.dims) {
+            *dim = dim.checked_add(add).ok_or(CalcError::DimensionMismatch)?;
+        }
+        Ok(Unit { dims, label: None })
+    }
+
+    pub fn div(&self, other: &Unit) -> Result<Unit, CalcError> {
+        if other.is_dimensionless() {
+            return Ok(self.clone());
+        }
+        let mut dims = self.dims;
+        for (dim, sub) in dims.iter_mut().zip(other.dims) {
+            *dim = dim.checked_sub(sub).ok_or(CalcError::DimensionMismatch)?;
+        }
+        Ok(Unit { dims, label: None })
+    }
+
+    pub fn pow(&self, n: i8) -> Result<Unit, CalcError> {
+        let mut dims = self.dims;
+        for dim in dims.iter_mut() {
+            *dim = dim.checked_mul(n).ok_or(CalcError::DimensionMismatch)?;
+        }
+        Ok(Unit { dims, label: None })
+    }
+
+    pub fn display(&self, si: &Float) -> Float {
+        match &self.label {
+            Some(label) => {
+                let value = Float::with_val(si.prec(), si / &label.scale);
+                match &label.offset {
+                    Some(offset) => value - offset,
+                    None => value,
+                }
+            },
+            None => si.clone(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        if let Some(label) = &self.label {
+            return label.name.clone();
+        }
+        let term = |index: usize, exp: i8| match exp {
+            1 => String::from(BASE[index]),
+            _ => format!("{}^{}", BASE[index], exp),
+        };
+        let numer: Vec<String> = self.dims.iter().enumerate()
+            .filter(|(_, &exp)| exp > 0)
+            .map(|(index, &exp)| term(index, exp))
+            .collect();
+        let denom = self.dims.iter().enumerate()
+            .filter(|(_, &exp)| exp < 0);
+        if numer.is_empty() {
+            return denom.map(|(index, &exp)| term(index, exp))
+                .collect::<Vec<_>>().join("*");
+        }
+        denom.fold(numer.join("*"), |name, (index, &exp)| {
+            format!("{}/{}", name, term(index, -exp))
+        })
+    }
+}
+
+pub fn resolve(name: &[u8], prec: u32) -> Option<(Float, Option<Float>, Unit)> {
+    let (def, prefix) = lookup(name)?;
+    let mut scale = parse_scale(def.scale, prec);
+    if let Some(prefix) = prefix {
+        scale *= parse_scale(prefix, prec);
+    }
+    let offset = def.offset.map(|offset| parse_scale(offset, prec));
+    let label = Label {
+        name: String::from_utf8_lossy(name).into_owned(),
+        scale: scale.clone(),
+        offset: offset.clone(),
+    };
+    Some((scale, offset, Unit { dims: def.dims, label: Some(label) }))
+}
+
+fn is_ident(byte: u8) -> bool {
+    byte.is_ascii_alphabetic()
+}
+
+pub fn parse_target(expr: &[u8], start: usize, prec: u32) -> Result<(Unit, usize), CalcError> {
+    let mut index = start;
+    while matches!(expr.get(index), Some(b' ' | b'\t')) {
+        index += 1;
+    }
+    let begin = index;
+    let mut scale = Float::with_val(prec, 1);
+    let mut dims = [0i8; 7];
+    let mut offset;
+    let mut terms = 0;
+    let mut divide = false;
+    let mut end;
+    loop {
+        while matches!(expr.get(index), Some(b' ' | b'\t')) {
+            index += 1;
+        }
+        let name_start = index;
+        while expr.get(index).is_some_and(|&b| is_ident(b)) {
+            index += 1;
+        }
+        if name_start == index {
+            return Err(CalcError::ExpressionError);
+        }
+        let name = &expr[name_start..index];
+        let (def, prefix) = lookup(name).ok_or(CalcError::UnitUndefined)?;
+        let mut exp: i8 = 1;
+        if expr.get(index) == Some(&b'^') {
+            let exp_start = index + 1;
+            index = exp_start;
+            if expr.get(index) == Some(&b'-') {
+                index += 1;
+            }
+            while expr.get(index).is_some_and(|b| b.is_ascii_digit()) {
+                index += 1;
+            }
+            exp = std::str::from_utf8(&expr[exp_start..index]).ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or(CalcError::ExpressionError)?;
+        }
+        if divide {
+            exp = exp.checked_neg().ok_or(CalcError::DimensionMismatch)?;
+        }
+        let mut term = parse_scale(def.scale, prec);
+        if let Some(prefix) = prefix {
+            term *= parse_scale(prefix, prec);
+        }
+        scale *= term.pow(exp as i32);
+        for (dim, base) in dims.iter_mut().zip(def.dims) {
+            *dim = base.checked_mul(exp)
+                .and_then(|add| dim.checked_add(add))
+                .ok_or(CalcError::DimensionMismatch)?;
+        }
+        offset = def.offset.filter(|_| exp == 1)
+            .map(|offset| parse_scale(offset, prec));
+        terms += 1;
+        end = index;
+        while matches!(expr.get(index), Some(b' ' | b'\t')) {
+            index += 1;
+        }
+        match expr.get(index) {
+            Some(b'*') => divide = false,
+            Some(b'/') => divide = true,
+            _ => break,
+        }
+        index += 1;
+    }
+    if terms > 1 {
+        offset = None;
+    }
+    let name = String::from_utf8_lossy(&expr[begin..end]);
+    let label = Label { name: name.into_owned(), scale, offset };
+    Ok((Unit { dims, label: Some(label) }, end))
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use calc::{CalcError, Calculator, LogBase};
+use common::eval;
+
+#[test]
+fn alias() {
+    assert_eq!(eval("arcsin(1)").unwrap(), eval("asin(1)").unwrap());
+    assert_eq!(eval("lg(100)").unwrap(), "2");
+    assert_eq!(eval("nCr(5, 2)").unwrap(), "10");
+    assert_eq!(eval("factorial(5)").unwrap(), "120");
+    assert_eq!(eval("gammaln(10)").unwrap(), eval("lngamma(10)").unwrap());
+    assert_eq!(eval("avg([1, 2, 3])").unwrap(), "2");
+}
+
+#[test]
+fn log() {
+    assert_eq!(eval("log(8)").unwrap(), "3");
+    let mut calc = Calculator::new(256);
+    calc.set_log(LogBase::Common);
+    assert_eq!(calc.run_round("log(100)", Some(10)).unwrap(), "2");
+    calc.set_log(LogBase::Natural);
+    assert_eq!(calc.run_round("log(100)", Some(10)).unwrap(), "4.605170186");
+}
+
+#[test]
+fn suggestion() {
+    assert!(matches!(eval("sinx(1)"), Err(CalcError::DidYouMean(name)) if name == "sin"));
+    assert!(matches!(eval("sni(1)"), Err(CalcError::FunctionUndefined)));
+    assert!(matches!(eval("x"), Err(CalcError::FunctionUndefined)));
+    assert!(matches!(eval("my_var + 1"), Err(CalcError::FunctionUndefined)));
+    assert!(matches!(eval("sin (1)"), Err(CalcError::DidYouMean(name)) if name == "sin"));
+    assert!(matches!(eval("sqr"), Err(CalcError::DidYouMean(name)) if name == "sqrt"));
+}
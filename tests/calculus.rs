@@ -0,0 +1,35 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn derivative() {
+    assert_eq!(eval("diff(x^2, x, 3)").unwrap(), "6");
+    assert_eq!(eval("diff(sin(x), x, 0)").unwrap(), "1");
+    assert_eq!(eval("diff(x^3, x, 2, 2)").unwrap(), "12");
+}
+
+#[test]
+fn integral() {
+    assert_eq!(eval("integrate(x^2, x, 0, 3)").unwrap(), "9");
+    assert_eq!(eval("integrate(ln(x), x, 0, 1)").unwrap(), "-1");
+    assert_eq!(eval("integrate_err(x^2, x, 0, 1)").unwrap(), "[0.3333333333, 0]");
+}
+
+#[test]
+fn root() {
+    assert_eq!(eval("solve(x^x-100, x, 3)").unwrap(), "3.5972850235");
+    assert_eq!(eval("solve(x^2-2, x, 0, 2)").unwrap(), "1.4142135624");
+    assert!(matches!(eval("solve(x^2+1, x, 1)"), Err(CalcError::NoConvergence)));
+    assert!(matches!(eval("solve(x^2+1, x, 0, 2)"), Err(CalcError::ParameterError)));
+}
+
+#[test]
+fn series() {
+    assert_eq!(eval("sum(k, 1, 100, k)").unwrap(), "5050");
+    assert_eq!(eval("prod(k, 1, 5, k)").unwrap(), "120");
+    assert_eq!(eval("prod(k, 2, 20, 1-1/k^2)").unwrap(), "0.525");
+    assert_eq!(eval("sum(k, 1, 0, k)").unwrap(), "0");
+    assert_eq!(eval("sum(k, 1, inf, 1/k^2)").unwrap(), "1.6449340668");
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn counting() {
+    assert_eq!(eval("ncr(5, 2)").unwrap(), "10");
+    assert_eq!(eval("npr(5, 2)").unwrap(), "20");
+    assert_eq!(eval("multinomial(2, 3, 4)").unwrap(), "1260");
+    assert_eq!(eval("subfactorial(5)").unwrap(), "44");
+    assert_eq!(eval("catalan(10)").unwrap(), "16796");
+    assert_eq!(eval("bell(5)").unwrap(), "52");
+    assert_eq!(eval("stirling(5, 2)").unwrap(), "15");
+    assert_eq!(eval("stirlingf(5, 2)").unwrap(), "50");
+}
+
+#[test]
+fn exact() {
+    assert_eq!(eval("fac(20)").unwrap(), "2432902008176640000");
+    assert_eq!(eval("fac(1000)/fac(999)").unwrap(), "1000");
+    assert_eq!(eval("ncr(1000, 500)").unwrap().len(), 300);
+}
+
+#[test]
+fn limits() {
+    assert!(matches!(eval("catalan(4294967295)"), Err(CalcError::LimitExceeded)));
+    assert!(matches!(eval("fac(200000)"), Err(CalcError::LimitExceeded)));
+    for expr in ["bell(2001)", "stirling(2001, 2)", "stirlingf(2001, 2)", "subfactorial(200000)"] {
+        assert!(matches!(eval(expr), Err(CalcError::LimitExceeded)), "{}", expr);
+    }
+    assert!(matches!(eval("bell(-1)"), Err(CalcError::ParameterError)));
+    assert!(matches!(eval("ncr(-1, 2)"), Err(CalcError::ParameterError)));
+    assert_eq!(eval("stirling(3, 5)").unwrap(), "0");
+    assert_eq!(eval("bell(0)").unwrap(), "1");
+}
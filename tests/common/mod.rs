@@ -0,0 +1,5 @@
+use calc::{CalcError, Calculator};
+
+pub fn eval(expr: &str) -> Result<String, CalcError> {
+    Calculator::new(256).run_round(expr, Some(10))
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn normal() {
+    assert_eq!(eval("normpdf(0)").unwrap(), "0.3989422804");
+    assert_eq!(eval("normcdf(1.96)").unwrap(), "0.9750021049");
+    assert_eq!(eval("norminv(0.975)").unwrap(), "1.9599639845");
+}
+
+#[test]
+fn continuous() {
+    assert_eq!(eval("tcdf(2, 10)").unwrap(), "0.9633059826");
+    assert_eq!(eval("chisqcdf(3.84, 1)").unwrap(), "0.9499564788");
+    assert_eq!(eval("fcdf(1, 2, 3)").unwrap(), "0.5352419985");
+    assert_eq!(eval("expcdf(1, 2)").unwrap(), "0.8646647168");
+}
+
+#[test]
+fn discrete() {
+    assert_eq!(eval("binompdf(2, 5, 0.5)").unwrap(), "0.3125");
+    assert_eq!(eval("binomcdf(2, 5, 0.5)").unwrap(), "0.5");
+    assert_eq!(eval("poisspdf(3, 2)").unwrap(), "0.1804470443");
+}
+
+#[test]
+fn arity() {
+    assert!(matches!(eval("normpdf(0, 0, 1, 5, 6)"), Err(CalcError::ParameterError)));
+    assert!(matches!(eval("tpdf(1)"), Err(CalcError::ParameterError)));
+}
@@ -0,0 +1,34 @@
+use calc::{CalcError, Calculator, Magnitude};
+
+#[test]
+fn full_integer() {
+    let digits = Calculator::new(256).run_round("fac(2000)", None).unwrap();
+    assert_eq!(digits.len(), 5736);
+    assert!(digits.starts_with("331627509245063324117539338057632403828111720810578039457193543706038077905600"));
+    assert!(digits.ends_with(&"0".repeat(499)));
+}
+
+#[test]
+fn high_precision() {
+    let digits = Calculator::new(100_000).run_round("1/3", None).unwrap();
+    assert!(digits.len() > 30_000);
+    assert!(digits[2..].bytes().all(|b| b == b'3'));
+}
+
+#[test]
+fn writer() {
+    let value = Calculator::new(256).run("fac(300)").unwrap();
+    let mut out = String::new();
+    value.write_round(&mut out, None).unwrap();
+    assert_eq!(out, value.to_round(None).unwrap());
+}
+
+#[test]
+fn magnitude() {
+    let mut calc = Calculator::new(256);
+    assert!(matches!(calc.run("10^800"), Err(CalcError::BeyondAccuracy)));
+    calc.reset();
+    calc.set_magnitude(Magnitude::Extended);
+    assert_eq!(calc.run_round("10^800", Some(6)).unwrap(), "1e800 (77 digits)");
+    assert_eq!(calc.run_round("3e5000", Some(6)).unwrap(), "3e5000 (77 digits)");
+}
@@ -0,0 +1,58 @@
+use calc::{CalcError, Calculator, Limits};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+fn nested(depth: usize) -> String {
+    format!("{}1{}", "(".repeat(depth), ")".repeat(depth))
+}
+
+#[test]
+fn depth() {
+    let mut calc = Calculator::new(256);
+    assert_eq!(calc.run_round(nested(200), None).unwrap(), "1");
+    assert!(matches!(calc.run(nested(300)), Err(CalcError::DepthExceeded)));
+    calc.reset();
+    calc.set_max_depth(1000);
+    assert_eq!(calc.run_round(nested(300), None).unwrap(), "1");
+    let call = format!("{}0{}", "abs(".repeat(500), ")".repeat(500));
+    assert_eq!(calc.run_round(call, None).unwrap(), "0");
+}
+
+#[test]
+fn budget() {
+    let mut calc = Calculator::new(256);
+    calc.set_limits(Limits { evaluations: 1000, factorial: 10, ..Default::default() });
+    assert!(matches!(calc.run("sum(k, 1, 100000, k)"), Err(CalcError::LimitExceeded)));
+    calc.reset();
+    assert!(matches!(calc.run("fac(11)"), Err(CalcError::LimitExceeded)));
+    calc.reset();
+    assert_eq!(calc.run_round("sum(k, 1, 100, k)", None).unwrap(), "5050");
+}
+
+#[test]
+fn timeout() {
+    let mut calc = Calculator::new(256);
+    calc.set_limits(Limits { timeout: Some(Duration::ZERO), ..Default::default() });
+    for expr in ["integrate(sin(x), x, 0, 1)", "sum(k, 1, 100, k)", "bell(50)", "stirling(50, 3)", "subfactorial(50)", "norminv(0.3)"] {
+        assert!(matches!(calc.run(expr), Err(CalcError::LimitExceeded)), "{}", expr);
+        calc.reset();
+    }
+    calc.set_limits(Limits::default());
+    assert_eq!(calc.run_round("bell(5)", None).unwrap(), "52");
+}
+
+#[test]
+fn cancel() {
+    let mut calc = Calculator::new(256);
+    calc.set_limits(Limits { evaluations: u64::MAX, ..Default::default() });
+    let token = calc.cancel_token();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        token.store(true, Ordering::Relaxed);
+    });
+    assert!(matches!(calc.run("sum(j, 1, 999999, sum(k, 1, 999999, k))"), Err(CalcError::Cancelled)));
+    handle.join().unwrap();
+    calc.reset();
+    assert_eq!(calc.run_round("1+1", None).unwrap(), "2");
+}
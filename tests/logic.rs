@@ -0,0 +1,24 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn comparison() {
+    assert_eq!(eval("1 < 2").unwrap(), "1");
+    assert_eq!(eval("2 <= 1").unwrap(), "0");
+    assert_eq!(eval("3 == 3").unwrap(), "1");
+    assert_eq!(eval("3 != 3").unwrap(), "0");
+    assert_eq!(eval("2*3 > 5 and 1").unwrap(), "1");
+    assert_eq!(eval("0 or 1").unwrap(), "1");
+    assert_eq!(eval("not(0)").unwrap(), "1");
+}
+
+#[test]
+fn conditional() {
+    assert_eq!(eval("if(1 > 2, 1/0, 5)").unwrap(), "5");
+    assert_eq!(eval("sum(k, 1, 10, if(k<3, k, 0))").unwrap(), "3");
+    assert_eq!(eval("clamp(15, 0, 10)").unwrap(), "10");
+    assert_eq!(eval("approx(0.1+0.2, 0.3)").unwrap(), "1");
+    assert!(matches!(eval("if(1 < 2, 1/0, 5)"), Err(CalcError::DivideByZero)));
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn arithmetic() {
+    assert_eq!(eval("[1, 2] + 1").unwrap(), "[2, 3]");
+    assert_eq!(eval("2 * [[1, 2], [3, 4]]").unwrap(), "[[2, 4], [6, 8]]");
+    assert_eq!(eval("[[1, 2], [3, 4]] * [[1, 0], [0, 1]]").unwrap(), "[[1, 2], [3, 4]]");
+    assert!(matches!(eval("[[1, 2], [3, 4]] + [[1, 2, 3]]"), Err(CalcError::ParameterError)));
+}
+
+#[test]
+fn linear_algebra() {
+    assert_eq!(eval("det([[1, 2], [3, 4]])").unwrap(), "-2");
+    assert_eq!(eval("inv([[1, 2], [3, 4]])").unwrap(), "[[-2, 1], [1.5, -0.5]]");
+    assert_eq!(eval("transpose([[1, 2], [3, 4]])").unwrap(), "[[1, 3], [2, 4]]");
+    assert_eq!(eval("rank([[1, 2], [2, 4]])").unwrap(), "1");
+    assert_eq!(eval("solve([[2, 0], [0, 4]], [2, 8])").unwrap(), "[1, 2]");
+    assert!(matches!(eval("inv([[1, 2], [2, 4]])"), Err(CalcError::DivideByZero)));
+    assert!(matches!(eval("det([[1, 2, 3], [4, 5, 6]])"), Err(CalcError::ParameterError)));
+}
+
+#[test]
+fn vector() {
+    assert_eq!(eval("dot([1, 2, 3], [4, 5, 6])").unwrap(), "32");
+    assert_eq!(eval("cross([1, 0, 0], [0, 1, 0])").unwrap(), "[0, 0, 1]");
+    assert_eq!(eval("norm([3, 4])").unwrap(), "5");
+}
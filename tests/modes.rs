@@ -0,0 +1,84 @@
+use calc::{Arithmetic, CalcError, Calculator, Numbers, Percent};
+
+fn ieee() -> Calculator {
+    let mut calc = Calculator::new(256);
+    calc.set_arithmetic(Arithmetic::Ieee);
+    calc
+}
+
+#[test]
+fn special_values() {
+    let mut calc = ieee();
+    assert_eq!(calc.run_round("1/0", Some(6)).unwrap(), "inf");
+    assert_eq!(calc.run_round("-1/0", Some(6)).unwrap(), "-inf");
+    assert_eq!(calc.run_round("0/0", Some(6)).unwrap(), "NaN");
+    assert_eq!(calc.run_round("ln(0)", Some(6)).unwrap(), "-inf");
+    assert_eq!(calc.run_round("-inf+1", Some(6)).unwrap(), "-inf");
+    assert_eq!(calc.run_round("nan", Some(6)).unwrap(), "NaN");
+}
+
+#[test]
+fn nan_propagation() {
+    let mut calc = ieee();
+    for expr in ["norminv(nan, 0, 1)", "chisqinv(0.5, inf)", "tinv(nan, 3)", "finv(0.5, nan, 2)", "chisqcdf(nan, 2)",
+        "det([[nan]])", "det([[1, nan], [2, 3]])", "ellipk(2)", "ellipe(2)", "ellipe(nan)"] {
+        assert_eq!(calc.run_round(expr, Some(6)).unwrap(), "NaN", "{}", expr);
+    }
+    assert_eq!(calc.run_round("ellipk(1)", Some(6)).unwrap(), "inf");
+    assert_eq!(calc.run_round("inv([[nan]])", Some(6)).unwrap(), "[[NaN]]");
+}
+
+#[test]
+fn strict() {
+    let mut calc = Calculator::new(256);
+    assert!(matches!(calc.run("1/0"), Err(CalcError::DivideByZero)));
+    calc.reset();
+    assert!(matches!(calc.run("ellipk(1)"), Err(CalcError::DomainError)));
+}
+
+fn decimal() -> Calculator {
+    let mut calc = Calculator::new(256);
+    calc.set_numbers(Numbers::Decimal);
+    calc
+}
+
+#[test]
+fn exact_decimal() {
+    let mut calc = decimal();
+    assert_eq!(calc.run_round("0.1+0.2", None).unwrap(), "0.3");
+    assert_eq!(calc.run_round("0.07*19.99", None).unwrap(), "1.3993");
+    assert_eq!(calc.run_round("1/3", None).unwrap(), "1/3");
+    assert_eq!(calc.run_round("1/3", Some(10)).unwrap(), "0.3333333333");
+    assert_eq!(calc.run_round("(1/2)^-2", None).unwrap(), "4");
+    assert_eq!(calc.run_round("2^0.5", Some(10)).unwrap(), "1.4142135624");
+}
+
+#[test]
+fn decimal_zero_power() {
+    let mut calc = decimal();
+    assert!(matches!(calc.run("0^-1"), Err(CalcError::DivideByZero)));
+    calc.reset();
+    calc.set_arithmetic(Arithmetic::Ieee);
+    assert_eq!(calc.run_round("0^-1", None).unwrap(), "inf");
+}
+
+#[test]
+fn modulo() {
+    let mut calc = Calculator::new(256);
+    assert_eq!(calc.run_round("-7%3", None).unwrap(), "-1");
+    assert_eq!(calc.run_round("mod(-7, 3)", None).unwrap(), "2");
+    assert_eq!(calc.run_round("rem(-7, 3)", None).unwrap(), "-1");
+    assert_eq!(calc.run_round("emod(-7, -3)", None).unwrap(), "2");
+    assert_eq!(calc.run_round("-7//2", None).unwrap(), "-4");
+}
+
+#[test]
+fn percentage() {
+    let mut calc = Calculator::new(256);
+    calc.set_percent(Percent::Percentage);
+    assert_eq!(calc.run_round("200+10%", None).unwrap(), "220");
+    assert_eq!(calc.run_round("200-10%", None).unwrap(), "180");
+    assert_eq!(calc.run_round("200*10%", None).unwrap(), "20");
+    assert_eq!(calc.run_round("200/10%", None).unwrap(), "2000");
+    assert_eq!(calc.run_round("50%", None).unwrap(), "0.5");
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn divisors() {
+    assert_eq!(eval("gcd(12, 18)").unwrap(), "6");
+    assert_eq!(eval("lcm(4, 6)").unwrap(), "12");
+    assert_eq!(eval("totient(36)").unwrap(), "12");
+    assert_eq!(eval("factor(360)").unwrap(), "2^3 × 3^2 × 5");
+}
+
+#[test]
+fn primes() {
+    assert_eq!(eval("isprime(97)").unwrap(), "1");
+    assert_eq!(eval("isprime(1)").unwrap(), "0");
+    assert_eq!(eval("isprime(-7)").unwrap(), "0");
+    assert_eq!(eval("nextprime(100)").unwrap(), "101");
+    assert_eq!(eval("prevprime(100)").unwrap(), "97");
+}
+
+#[test]
+fn modular() {
+    assert_eq!(eval("modpow(2, 10, 1000)").unwrap(), "24");
+    assert_eq!(eval("modinv(3, 11)").unwrap(), "4");
+    assert_eq!(eval("jacobi(2, 7)").unwrap(), "1");
+    assert!(matches!(eval("modinv(2, 4)"), Err(CalcError::ParameterError)));
+}
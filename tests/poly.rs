@@ -0,0 +1,22 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn roots() {
+    assert_eq!(eval("roots([1, 0, -2])").unwrap(), "[-1.4142135624, 1.4142135624]");
+    assert_eq!(eval("roots([1, 0, 1])").unwrap(), "[-1i, 1i]");
+    assert_eq!(eval("roots([1, 2, 3, 4, 5, 6, 7])").unwrap(), "[-1.307869744 - 0.5932947074i, \
+        -1.307869744 + 0.5932947074i, -0.4025091254 - 1.3416668278i, -0.4025091254 + 1.3416668278i, \
+        0.7103788693 - 1.1068452984i, 0.7103788693 + 1.1068452984i]");
+    assert!(matches!(eval("roots([0])"), Err(CalcError::ParameterError)));
+}
+
+#[test]
+fn helpers() {
+    assert_eq!(eval("polyval([1, 2, 3], 2)").unwrap(), "11");
+    assert_eq!(eval("polyval([1, 2], [1, 2])").unwrap(), "[3, 4]");
+    assert_eq!(eval("polyder([1, 2, 3])").unwrap(), "[2, 2]");
+    assert_eq!(eval("polyfit([0, 1, 2], [1, 3, 5], 1)").unwrap(), "[2, 1]");
+}
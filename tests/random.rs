@@ -0,0 +1,43 @@
+use calc::Calculator;
+
+fn seeded(seed: u64) -> Calculator {
+    let mut calc = Calculator::new(256);
+    calc.seed(seed);
+    calc
+}
+
+#[test]
+fn seed() {
+    let (mut a, mut b) = (seeded(42), seeded(42));
+    for expr in ["rand()", "randint(1, 6)", "randn(0, 1)", "choose([1, 5, 9])"] {
+        assert_eq!(a.run_round(expr, Some(10)).unwrap(), b.run_round(expr, Some(10)).unwrap());
+    }
+}
+
+#[test]
+fn range() {
+    let mut calc = seeded(7);
+    for _ in 0..100 {
+        let value: u32 = calc.run_round("randint(1, 6)", None).unwrap().parse().unwrap();
+        assert!((1..=6).contains(&value));
+    }
+}
+
+#[test]
+fn volatile() {
+    let mut calc = seeded(1);
+    calc.run("rand() + 1").unwrap();
+    assert!(calc.is_volatile());
+    calc.run("1 + 1").unwrap();
+    assert!(!calc.is_volatile());
+}
+
+#[test]
+fn shared() {
+    let (mut a, mut c) = (seeded(42), seeded(42));
+    let mut b = a.clone();
+    a.run_round("rand()", Some(10)).unwrap();
+    c.run_round("rand()", Some(10)).unwrap();
+    assert_eq!(b.run_round("rand()", Some(10)).unwrap(), c.run_round("rand()", Some(10)).unwrap());
+    assert_eq!(a.run_round("sum(k, 1, 3, rand())", Some(10)).unwrap(), c.run_round("sum(k, 1, 3, rand())", Some(10)).unwrap());
+}
@@ -0,0 +1,41 @@
+use calc::{Arithmetic, Calculator, Magnitude, Numbers, Percent};
+
+const INPUTS: &[&[u8]] = &[
+    b"", b" ", b"(", b")", b"()", b"+", b"-", b"--1", b"1+", b"*2", b"1e", b"1e+", b".", b"1..2",
+    b"2x", b"sin", b"sin(", b"sin()", b"fac(-1)", b"fac(1.5)", b"ncr(2, 5)", b"[", b"[]", b"[[]]",
+    b"[1, [2]]", b"det([])", b"roots([])", b"sum(k, 1)", b"solve(x, x)", b"integrate(x, x, 0)",
+    b"1/0", b"0/0", b"0^-1", b"-1^0.5", b"10^10^10", b"1e5000", b"1e-5000", b"5 m to s",
+    b"1 to", b"to m", b"\xff\xfe", b"1\x00", b"\xe4\xb8\x80", b"if(1)", b"modinv(0, 0)",
+    b"factor(0)", b"percentile([1], -1)", b"randint(6, 1)", b"lambertw(-1)", b"log(0)",
+];
+
+#[test]
+fn no_panic() {
+    let mut calc = Calculator::new(128);
+    check(&mut calc);
+    calc.set_magnitude(Magnitude::Extended);
+    check(&mut calc);
+    calc.set_arithmetic(Arithmetic::Ieee);
+    check(&mut calc);
+    calc.set_numbers(Numbers::Decimal);
+    check(&mut calc);
+    calc.set_percent(Percent::Percentage);
+    check(&mut calc);
+}
+
+fn check(calc: &mut Calculator) {
+    for input in INPUTS {
+        for digits in [None, Some(0), Some(6)] {
+            if calc.run_round(input, digits).is_err() {
+                calc.reset();
+            }
+        }
+    }
+}
+
+#[test]
+fn unbounded_digits() {
+    let mut calc = Calculator::new(256);
+    assert!(calc.run_round("-2/3", None).unwrap().starts_with("-0.666666"));
+    assert!(calc.run_round("1/3", None).unwrap().starts_with("0.333333"));
+}
@@ -0,0 +1,30 @@
+use calc::{CalcError, Calculator};
+
+#[test]
+fn statements() {
+    let script = "a = 2\nb = a * 3 # six\n# comment\n1/0; b + 1\nc = 5 // 2 # floor\n";
+    let results = Calculator::new(256).run_script(script);
+    let lines: Vec<_> = results.iter().map(|statement| (statement.line, statement.name.as_deref())).collect();
+    assert_eq!(lines, [(1, Some("a")), (2, Some("b")), (4, None), (4, None), (5, Some("c"))]);
+    let value = |index: usize| results[index].result.as_ref().unwrap().to_round(Some(6)).unwrap();
+    assert_eq!(value(0), "2");
+    assert_eq!(value(1), "6");
+    assert!(matches!(results[2].result, Err(CalcError::DivideByZero)));
+    assert_eq!(value(3), "7");
+    assert_eq!(value(4), "2");
+}
+
+#[test]
+fn invalid_name() {
+    let results = Calculator::new(256).run_script("to = 1\n2x = 3");
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|statement| matches!(statement.result, Err(CalcError::ExpressionError))));
+}
+
+#[test]
+fn comment() {
+    let results = Calculator::new(256).run_script("x = 10 # note\n#x = 1\nx // 3");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].result.as_ref().unwrap().to_round(Some(6)).unwrap(), "10");
+    assert_eq!(results[1].result.as_ref().unwrap().to_round(Some(6)).unwrap(), "3");
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn bessel() {
+    assert_eq!(eval("besselj(0, 1)").unwrap(), "0.7651976866");
+    assert_eq!(eval("bessely(0, 1)").unwrap(), "0.0882569642");
+}
+
+#[test]
+fn gamma() {
+    assert_eq!(eval("lngamma(10)").unwrap(), "12.8018274801");
+    assert_eq!(eval("beta(2, 3)").unwrap(), "0.0833333333");
+    assert_eq!(eval("gammainc(1, 1)").unwrap(), "0.3678794412");
+}
+
+#[test]
+fn other() {
+    assert_eq!(eval("agm(1, 2)").unwrap(), "1.456791031");
+    assert_eq!(eval("lambertw(1)").unwrap(), "0.5671432904");
+    assert_eq!(eval("lambertw(-0.2, -1)").unwrap(), "-2.5426413578");
+    assert_eq!(eval("ellipk(0.5)").unwrap(), "1.8540746773");
+    assert_eq!(eval("ellipe(0.5)").unwrap(), "1.350643881");
+    assert!(matches!(eval("agm(-1, 2)"), Err(CalcError::DomainError)));
+}
+
+#[test]
+fn trigonometric() {
+    assert_eq!(eval("asec(2)").unwrap(), "1.0471975512");
+    assert_eq!(eval("acsc(2)").unwrap(), "0.5235987756");
+    assert_eq!(eval("acot(1)").unwrap(), "0.7853981634");
+    assert_eq!(eval("asech(0.5)").unwrap(), "1.3169578969");
+    assert_eq!(eval("acsch(1)").unwrap(), "0.881373587");
+    assert_eq!(eval("acoth(2)").unwrap(), "0.5493061443");
+    assert_eq!(eval("gd(1)").unwrap(), "0.8657694832");
+    assert_eq!(eval("sinc(0)").unwrap(), "1");
+    assert_eq!(eval("versin(1)").unwrap(), "0.4596976941");
+    assert_eq!(eval("hypot(3, 4)").unwrap(), "5");
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn summary() {
+    assert_eq!(eval("mean([1, 2, 3.5, 7])").unwrap(), "3.375");
+    assert_eq!(eval("median([1, 2, 3.5, 7])").unwrap(), "2.75");
+    assert_eq!(eval("mode([1, 2, 2, 3])").unwrap(), "2");
+    assert_eq!(eval("geomean([1, 4, 16])").unwrap(), "4");
+    assert_eq!(eval("min([3, 1, 2])").unwrap(), "1");
+    assert_eq!(eval("max([3, 1, 2])").unwrap(), "3");
+    assert_eq!(eval("sum([1, 2, 3])").unwrap(), "6");
+}
+
+#[test]
+fn spread() {
+    assert_eq!(eval("var([1, 2, 3, 4])").unwrap(), "1.6666666667");
+    assert_eq!(eval("stdev([2, 4, 4, 4, 5, 5, 7, 9])").unwrap(), "2.1380899353");
+    assert_eq!(eval("pstdev([2, 4, 4, 4, 5, 5, 7, 9])").unwrap(), "2");
+    assert_eq!(eval("percentile([1, 2, 3], 90)").unwrap(), "2.8");
+}
+
+#[test]
+fn invalid() {
+    assert!(matches!(eval("mean([])"), Err(CalcError::ParameterError)));
+    assert!(matches!(eval("stdev([1])"), Err(CalcError::ParameterError)));
+    assert!(matches!(eval("percentile([1, 2, 3], 101)"), Err(CalcError::ParameterError)));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use calc::CalcError;
+use common::eval;
+
+#[test]
+fn conversion() {
+    assert_eq!(eval("3.5 km/h to m/s").unwrap(), "0.9722222222 m/s");
+    assert_eq!(eval("1 mi to km").unwrap(), "1.609344 km");
+    assert_eq!(eval("1 kg in g").unwrap(), "1000 g");
+    assert_eq!(eval("2 N * 3 m to J").unwrap(), "6 J");
+}
+
+#[test]
+fn temperature() {
+    assert_eq!(eval("72 degF to degC").unwrap(), "22.2222222222 degC");
+    assert_eq!(eval("100 degC to degF").unwrap(), "212 degF");
+    assert_eq!(eval("20 degC - 10 degC").unwrap(), "10 K");
+    assert_eq!(eval("20 degC > 10 degC").unwrap(), "1");
+    for expr in ["72 degF + 10 degF", "10 degC * 2", "2 * 10 degC", "10 degC / 2", "(20 degC)^2", "20 degC - 5 K"] {
+        assert!(matches!(eval(expr), Err(CalcError::DimensionMismatch)), "{}", expr);
+    }
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(eval("5 m + 20 cm").unwrap(), "5.2 m");
+    assert_eq!(eval("3 m * 2").unwrap(), "6 m");
+    assert_eq!(eval("5m").unwrap(), "5 m");
+    assert!(matches!(eval("5 m + 2 s"), Err(CalcError::DimensionMismatch)));
+}
+
+#[test]
+fn juxtaposition() {
+    assert_eq!(eval("10 m / 2 s").unwrap(), "5 m/s");
+    assert_eq!(eval("100 km / 2 h to km/h").unwrap(), "50 km/h");
+    assert_eq!(eval("5 kg m / s^2 to N").unwrap(), "5 N");
+    assert_eq!(eval("5 m % 2 m").unwrap(), "1 m");
+    assert_eq!(eval("3 m^2").unwrap(), "3 m^2");
+    assert_eq!(eval("2^3 m").unwrap(), "8 m");
+    assert!(matches!(eval("10 m / 2 s to km"), Err(CalcError::DimensionMismatch)));
+}
+
+#[test]
+fn malformed() {
+    assert!(matches!(eval("1e"), Err(CalcError::InvalidNumber)));
+    assert!(matches!(eval("2x"), Err(CalcError::ExpressionError)));
+    assert!(matches!(eval("5 foo"), Err(CalcError::UnitUndefined)));
+    assert!(matches!(eval("(2) foo"), Err(CalcError::UnitUndefined)));
+}